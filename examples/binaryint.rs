@@ -0,0 +1,13 @@
+/** Problem link: https://www.luogu.com.cn/problem/P1303 */
+use std::io::stdin;
+use zhl_algorithm_template_rs::math::bigint::BinaryInt;
+
+fn main() {
+    let mut s = String::new();
+    stdin().read_line(&mut s).unwrap();
+    let a: BinaryInt = s.trim().parse().unwrap();
+    s.clear();
+    stdin().read_line(&mut s).unwrap();
+    let b: BinaryInt = s.trim().parse().unwrap();
+    println!("{}", a * b);
+}
@@ -0,0 +1,18 @@
+/** Problem link: https://www.luogu.com.cn/problem/P4777 */
+use std::io::stdin;
+use zhl_algorithm_template_rs::math::basic::crt_u64;
+
+fn main() {
+    let mut s = String::new();
+    stdin().read_line(&mut s).unwrap();
+    let n: usize = s.trim().parse().unwrap();
+    let congruences = (0..n).map(|_| {
+        s.clear();
+        stdin().read_line(&mut s).unwrap();
+        let mut split = s.split_whitespace();
+        let m: u64 = split.next().unwrap().parse().unwrap();
+        let r: u64 = split.next().unwrap().parse().unwrap();
+        (r, m)
+    }).collect::<Vec<_>>();
+    println!("{}", crt_u64(&congruences).unwrap().0);
+}
@@ -0,0 +1,19 @@
+/** Problem link: https://www.luogu.com.cn/problem/U82118 */
+use std::io::stdin;
+use zhl_algorithm_template_rs::math::prime::{DeterministicMillerRabin, PrimalityTest};
+fn main() {
+    let mut miller_rabin = DeterministicMillerRabin::new();
+    let mut input = String::new();
+    while let Ok(x) = stdin().read_line(&mut input) {
+        if x == 0 {
+            break;
+        }
+        let n: u64 = input.trim().parse().unwrap();
+        if miller_rabin.is_prime_u64(&n) {
+            println!("Y");
+        } else {
+            println!("N");
+        }
+        input.clear();
+    }
+}
@@ -0,0 +1,17 @@
+/** Problem link: https://www.luogu.com.cn/problem/P3846 */
+use std::io::stdin;
+use zhl_algorithm_template_rs::{math::{discretelog::DiscreteLog, prime::{MillerRabin, PollardRho}}, random::generator::{Pseudorandom64, MT19937_64}};
+
+fn main() {
+    let rng = MT19937_64::new(None);
+    let miller_rabin = MillerRabin::new(&rng, None);
+    let pollard_rho = PollardRho::new(&rng, &miller_rabin);
+    let mut discrete_log = DiscreteLog::new(&pollard_rho);
+    let mut s = String::new();
+    stdin().read_line(&mut s).unwrap();
+    let v = s.split_whitespace().map(|x| x.parse::<u64>().unwrap()).collect::<Vec<_>>();
+    match discrete_log.discrete_log(&v[1], &v[2], &v[0]) {
+        Some(x) => println!("{}", x),
+        None => println!("no solution"),
+    }
+}
@@ -0,0 +1,28 @@
+/** Problem link: https://www.luogu.com.cn/problem/P4213 */
+use std::io::{stdin, Read};
+use zhl_algorithm_template_rs::math::{multiplicative::DuSieve, prime::{Sieve, DeterministicMillerRabin}};
+
+fn main() {
+    let mut s = String::new();
+    stdin().read_to_string(&mut s).unwrap();
+    let mut it = s.split_whitespace().map(|x| x.parse::<u64>().unwrap());
+    let t = it.next().unwrap();
+    let m = 2000000;
+    let mut sieve = Sieve::new(m, &DeterministicMillerRabin::new());
+    sieve.compute_phi();
+    sieve.compute_mu();
+    let mut sum_phi = vec![0i64; m + 1];
+    let mut sum_mu = vec![0i64; m + 1];
+    for i in 1..=m {
+        sum_phi[i] = sum_phi[i - 1] + sieve.phi(i) as i64;
+        sum_mu[i] = sum_mu[i - 1] + sieve.mu(i) as i64;
+    }
+    let mut out = String::new();
+    for _ in 0..t {
+        let n = it.next().unwrap();
+        let mut phi = DuSieve::new(n, sum_phi.clone(), |x| x as i64, |x| (x as i64) * (x as i64 + 1) / 2);
+        let mut mu = DuSieve::new(n, sum_mu.clone(), |x| x as i64, |x| if x >= 1 { 1 } else { 0 });
+        out.push_str(&format!("{} {}\n", phi.get(n), mu.get(n)));
+    }
+    print!("{}", out);
+}
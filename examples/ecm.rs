@@ -0,0 +1,32 @@
+use zhl_algorithm_template_rs::{math::prime::{BailliePSW, ECM, ExtractPrimeFactors, PrimalityTest, PrimeGenerator}, random::generator::{MT19937_64, Pseudorandom64}};
+fn main() {
+    let rng = MT19937_64::new(None);
+    let mut tester = BailliePSW::new();
+    let mut gen = PrimeGenerator::new(&rng, &tester);
+    let mut ecm = ECM::new(&rng, &tester);
+    for _ in 0..20 {
+        let p = gen.gen_prime_in_range_u128(1u128 << 59..1u128 << 60).unwrap();
+        let q = gen.gen_prime_in_range_u128(1u128 << 63..1u128 << 64).unwrap();
+        let mut factors = ecm.extract_prime_factors_u128(&(p * q));
+        factors.sort();
+        assert_eq!(factors, vec![p, q]);
+    }
+    for _ in 0..20 {
+        let p = gen.gen_prime_in_range_u128(1u128 << 39..1u128 << 40).unwrap();
+        let q = gen.gen_prime_in_range_u128(1u128 << 40..1u128 << 41).unwrap();
+        let r = gen.gen_prime_in_range_u128(1u128 << 41..1u128 << 42).unwrap();
+        let mut factors = ecm.extract_prime_factors_u128(&(p * q * r));
+        factors.sort();
+        assert_eq!(factors, vec![p, q, r]);
+        assert!(factors.iter().all(|f| tester.is_prime_u128(f)));
+    }
+    let n = 1000003u128 * 1000003 * 1000033 * 1000037 * 7 * 7;
+    let mut factors = ecm.extract_prime_factors_u128(&n);
+    factors.sort();
+    assert_eq!(factors, vec![7, 7, 1000003, 1000003, 1000033, 1000037]);
+    let mut factors = ecm.extract_prime_factors_u64(&(4294967291u64 * 4294967279));
+    factors.sort();
+    assert_eq!(factors, vec![4294967279, 4294967291]);
+
+    println!("ecm test passed!");
+}
@@ -0,0 +1,30 @@
+use zhl_algorithm_template_rs::math::prime::{prime_count, prime_sum, LucyHedgehog};
+fn main() {
+    let limit = 1000000usize;
+    let mut composite = vec![false; limit + 1];
+    let mut count = vec![0u64; limit + 1];
+    let mut sum = vec![0u128; limit + 1];
+    for i in 2..=limit {
+        if !composite[i] {
+            for j in (i * i..=limit).step_by(i) {
+                composite[j] = true;
+            }
+        }
+        count[i] = count[i - 1] + !composite[i] as u64;
+        sum[i] = sum[i - 1] + if composite[i] { 0 } else { i as u128 };
+    }
+    for n in 0..2000 {
+        assert_eq!(prime_count(n as u64), count[n]);
+        assert_eq!(prime_sum(n as u64), sum[n]);
+    }
+    let n = limit as u64;
+    let lucy = LucyHedgehog::new(n, |_| 1u64, |v| v.saturating_sub(1));
+    for i in 1..=n {
+        assert_eq!(lucy.get(n / i), count[(n / i) as usize]);
+    }
+    assert_eq!(prime_sum(2000000), 142913828922);
+    assert_eq!(prime_count(10000000000), 455052511);
+    assert_eq!(prime_count(100000000000), 4118054813);
+
+    println!("prime count test passed!");
+}
@@ -0,0 +1,22 @@
+/** Problem link: https://www.luogu.com.cn/problem/P5325 */
+use std::io::stdin;
+use zhl_algorithm_template_rs::math::{modint::ModInt1000000007, multiplicative::min25_sieve, prime::LucyHedgehog};
+
+type Mint = ModInt1000000007;
+
+fn main() {
+    let mut s = String::new();
+    stdin().read_line(&mut s).unwrap();
+    let n: u64 = s.trim().parse().unwrap();
+    let m = 1000000007u128;
+    let sum1 = LucyHedgehog::new(n, Mint::from, |v| Mint::from((v as u128 * (v as u128 + 1) / 2 % m) as u64) - Mint::one());
+    let sum2 = LucyHedgehog::new(n, |p| Mint::from(p) * Mint::from(p), |v| {
+        let v = v as u128;
+        Mint::from((v * (v + 1) / 2 % m * (2 * v + 1) % m) as u64) / Mint::from(3u64) - Mint::one()
+    });
+    let ans = min25_sieve(n, |v| sum2.get(v) - sum1.get(v), |p, e| {
+        let pe = Mint::from(p).pow(e as u64);
+        pe * (pe - Mint::one())
+    });
+    println!("{}", ans);
+}
@@ -0,0 +1,19 @@
+/** Problem link: https://www.luogu.com.cn/problem/P2613 */
+use std::io::stdin;
+use zhl_algorithm_template_rs::math::modint::ModInt;
+
+type Mint = ModInt<19260817>;
+
+fn main() {
+    let mut s = String::new();
+    stdin().read_line(&mut s).unwrap();
+    let a: Mint = s.trim().parse().unwrap();
+    s.clear();
+    stdin().read_line(&mut s).unwrap();
+    let b: Mint = s.trim().parse().unwrap();
+    if b.is_zero() {
+        println!("Angry!");
+    } else {
+        println!("{}", a / b);
+    }
+}
@@ -0,0 +1,20 @@
+/** Problem link: https://www.luogu.com.cn/problem/P5491 */
+use std::io::{stdin, Read};
+use zhl_algorithm_template_rs::math::modroot::sqrt_mod_cipolla;
+
+fn main() {
+    let mut s = String::new();
+    stdin().read_to_string(&mut s).unwrap();
+    let mut it = s.split_whitespace().map(|x| x.parse::<u64>().unwrap());
+    let t = it.next().unwrap();
+    let mut out = String::new();
+    for _ in 0..t {
+        let (n, p) = (it.next().unwrap(), it.next().unwrap());
+        match sqrt_mod_cipolla(&n, &p) {
+            None => out.push_str("Hola!\n"),
+            Some(0) => out.push_str("0\n"),
+            Some(x) => out.push_str(&format!("{} {}\n", x, p - x)),
+        }
+    }
+    print!("{}", out);
+}
@@ -0,0 +1,105 @@
+/** Problem link: https://www.luogu.com.cn/problem/P3733 */
+use std::io::{stdin, Read, Write, stdout, BufWriter};
+use zhl_algorithm_template_rs::math::linearequation::{OfflineXorLinearEquationSystem, XorBasisAnswer, XorBasisOperation, ZeroOneLinearEquation};
+
+const L: usize = 1000;
+
+fn parse(s: &str) -> Vec<u128> {
+    let mut f = vec![0u128; L.div_ceil(128)];
+    for (j, c) in s.bytes().rev().enumerate() {
+        if c == b'1' {
+            f[j >> 7] |= 1 << (j & 127);
+        }
+    }
+    f
+}
+
+fn to_equation(f: &[u128]) -> ZeroOneLinearEquation {
+    let mut equation = ZeroOneLinearEquation::new(L);
+    for j in 0..L {
+        if (f[j >> 7] >> (j & 127)) & 1 == 1 {
+            equation.set_i(j);
+        }
+    }
+    equation
+}
+
+fn main() {
+    let mut s = String::new();
+    stdin().read_to_string(&mut s).unwrap();
+    let mut split = s.split_ascii_whitespace();
+    let n = split.next().unwrap().parse::<usize>().unwrap();
+    let m = split.next().unwrap().parse::<usize>().unwrap();
+    let p = split.next().unwrap().parse::<usize>().unwrap();
+
+    let mut edges = vec![vec![]; n + 1];
+    let mut extra = vec![];
+    for _ in 0..m {
+        let x = split.next().unwrap().parse::<usize>().unwrap();
+        let y = split.next().unwrap().parse::<usize>().unwrap();
+        let z = parse(split.next().unwrap());
+        edges[x].push((y, z.clone()));
+        edges[y].push((x, z.clone()));
+        extra.push((x, y, z));
+    }
+
+    // the xor distances from 1 along a spanning tree, and every edge closes a cycle with the tree
+    let mut dist: Vec<Option<Vec<u128>>> = vec![None; n + 1];
+    dist[1] = Some(vec![0u128; L.div_ceil(128)]);
+    let mut stack = vec![1];
+    while let Some(u) = stack.pop() {
+        let du = dist[u].clone().unwrap();
+        for (v, z) in &edges[u] {
+            if dist[*v].is_none() {
+                dist[*v] = Some(du.iter().zip(z).map(|(a, b)| a ^ b).collect());
+                stack.push(*v);
+            }
+        }
+    }
+    let cycle = |x: usize, y: usize, z: &[u128]| -> ZeroOneLinearEquation {
+        let f: Vec<u128> = dist[x].as_ref().unwrap().iter().zip(dist[y].as_ref().unwrap()).zip(z).map(|((a, b), c)| a ^ b ^ c).collect();
+        to_equation(&f)
+    };
+
+    let mut system = OfflineXorLinearEquationSystem::new(L);
+    for (x, y, z) in &extra {
+        system.push(XorBasisOperation::Insert(cycle(*x, *y, z)));
+    }
+    system.push(XorBasisOperation::GetMax);
+    let mut added = vec![];
+    for _ in 0..p {
+        match split.next().unwrap() {
+            "Add" => {
+                let x = split.next().unwrap().parse::<usize>().unwrap();
+                let y = split.next().unwrap().parse::<usize>().unwrap();
+                let z = parse(split.next().unwrap());
+                let equation = cycle(x, y, &z);
+                system.push(XorBasisOperation::Insert(equation.clone()));
+                added.push((x, y, Some(equation)));
+            },
+            "Cancel" => {
+                let k = split.next().unwrap().parse::<usize>().unwrap() - 1;
+                system.push(XorBasisOperation::Delete(added[k].2.take().unwrap()));
+            },
+            _ => {
+                let k = split.next().unwrap().parse::<usize>().unwrap() - 1;
+                let z = parse(split.next().unwrap());
+                let (x, y, _) = added[k];
+                let equation = cycle(x, y, &z);
+                system.push(XorBasisOperation::Delete(added[k].2.replace(equation.clone()).unwrap()));
+                system.push(XorBasisOperation::Insert(equation));
+            },
+        }
+        system.push(XorBasisOperation::GetMax);
+    }
+
+    let mut out = BufWriter::new(stdout());
+    for answer in system.solve() {
+        if let XorBasisAnswer::GetMax(mx) = answer {
+            match mx.highest_one_bit() {
+                None => writeln!(out, "0").unwrap(),
+                Some(h) => writeln!(out, "{}", (0..=h).rev().map(|j| if mx.get_i(j) { '1' } else { '0' }).collect::<String>()).unwrap(),
+            }
+        }
+    }
+}
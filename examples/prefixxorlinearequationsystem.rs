@@ -0,0 +1,32 @@
+/** Problem link: https://www.luogu.com.cn/problem/CF1100F */
+use std::io::{stdin, Read, Write, stdout, BufWriter};
+use zhl_algorithm_template_rs::math::linearequation::PrefixXorLinearEquationSystem;
+
+fn main() {
+    let mut s = String::new();
+    stdin().read_to_string(&mut s).unwrap();
+    let mut split = s.split_ascii_whitespace().map(|x| x.parse::<usize>().unwrap());
+    let n = split.next().unwrap();
+
+    let mut system = PrefixXorLinearEquationSystem::with_capacity(20, n);
+    for _ in 0..n {
+        let a = split.next().unwrap();
+        let mut equation = system.new_equation();
+        for j in 0..20 {
+            if (a >> j) & 1 == 1 {
+                equation.set_i(j);
+            }
+        }
+        system.insert_basis(equation);
+    }
+
+    let q = split.next().unwrap();
+    let mut out = BufWriter::new(stdout());
+    for _ in 0..q {
+        let l = split.next().unwrap() - 1;
+        let r = split.next().unwrap() - 1;
+        let mx = system.get_max(l, r);
+        let ans = (0..20).filter(|&j| mx.get_i(j)).map(|j| 1 << j).sum::<usize>();
+        writeln!(out, "{}", ans).unwrap();
+    }
+}
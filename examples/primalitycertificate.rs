@@ -0,0 +1,27 @@
+use zhl_algorithm_template_rs::{math::{prime::{BailliePSW, ECM, PrimeGenerator}, primalitycertificate::PrimalityCertificate}, random::generator::{MT19937_64, Pseudorandom64}};
+fn main() {
+    let rng = MT19937_64::new(None);
+    let tester = BailliePSW::new();
+    let mut gen = PrimeGenerator::new(&rng, &tester);
+    let mut ecm = ECM::new(&rng, &tester);
+    for _ in 0..100 {
+        let p = gen.gen_prime_in_range_u64(1u64 << 63..u64::MAX).unwrap();
+        let certificate = PrimalityCertificate::generate_u64(&mut ecm, &p).unwrap();
+        assert!(certificate.n == p as u128 && certificate.verify());
+    }
+    for _ in 0..20 {
+        let p = gen.gen_prime_in_range_u128(1u128 << 126..1u128 << 127).unwrap();
+        let mut certificate = PrimalityCertificate::generate_u128(&mut ecm, &p).unwrap();
+        assert!(certificate.n == p && certificate.verify());
+        certificate.n += 2;
+        assert!(!certificate.verify());
+    }
+    let mersenne = (1u128 << 127) - 1;
+    assert!(PrimalityCertificate::generate_u128(&mut ecm, &mersenne).unwrap().verify());
+    assert!(PrimalityCertificate::generate_u128(&mut ecm, &(mersenne - 2)).is_none());
+    assert!(PrimalityCertificate::generate_u64(&mut ecm, &561).is_none());
+    assert!(PrimalityCertificate::generate_u64(&mut ecm, &3825123056546413051).is_none());
+    assert!(PrimalityCertificate::generate_u64(&mut ecm, &65537).unwrap().verify());
+
+    println!("primality certificate test passed!");
+}
@@ -0,0 +1,27 @@
+use zhl_algorithm_template_rs::{math::prime::{BailliePSW, NextPrimes, PrevPrimes, PrimalityTest, PrimeGenerator}, random::generator::{MT19937_64, Pseudorandom64}};
+fn main() {
+    let mut tester = BailliePSW::new();
+    let mut gen = PrimeGenerator::new(&MT19937_64::new(None), &tester);
+    for _ in 0..10000 {
+        let p = gen.gen_prime_in_range_u32(1000000000u32..2000000000u32).unwrap();
+        assert!((1000000000..2000000000).contains(&p) && tester.is_prime_u32(&p));
+        let p = gen.gen_prime_in_range_u64(1u64 << 62..u64::MAX).unwrap();
+        assert!(p >= 1 << 62 && tester.is_prime_u64(&p));
+        let p = gen.gen_prime_in_range_u128(1u128 << 120..u128::MAX).unwrap();
+        assert!(p >= 1 << 120 && tester.is_prime_u128(&p));
+        let p = gen.gen_safe_prime_in_range_u64(1u64 << 40..1u64 << 41).unwrap();
+        assert!(tester.is_prime_u64(&p) && tester.is_prime_u64(&(p / 2)));
+        let p = gen.gen_ntt_prime_in_range_u32(20, 0u32..1u32 << 30).unwrap();
+        assert!(p % (1 << 20) == 1 && tester.is_prime_u32(&p));
+    }
+    assert_eq!(gen.gen_prime_in_range_u64(24u64..29u64), None);
+
+    let primes: Vec<u64> = NextPrimes::new(&tester, 0u64).take(10).collect();
+    assert_eq!(primes, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    let primes: Vec<u64> = PrevPrimes::new(&tester, 30u64).collect();
+    assert_eq!(primes, vec![29, 23, 19, 17, 13, 11, 7, 5, 3, 2]);
+    assert_eq!(PrevPrimes::new(&tester, u64::MAX).next(), Some(u64::MAX - 58));
+    assert_eq!(NextPrimes::new(&tester, u64::MAX - 58).next(), None);
+
+    println!("prime generator test passed!");
+}
@@ -0,0 +1,10 @@
+/** Problem link: https://www.luogu.com.cn/problem/P1835 */
+use std::io::stdin;
+use zhl_algorithm_template_rs::math::prime::SegmentedSieve;
+
+fn main() {
+    let mut s = String::new();
+    stdin().read_line(&mut s).unwrap();
+    let v = s.split_whitespace().map(|x| x.parse::<u64>().unwrap()).collect::<Vec<_>>();
+    println!("{}", SegmentedSieve::new(v[0], v[1]).primes().count());
+}
@@ -0,0 +1,17 @@
+/** Problem link: https://www.luogu.com.cn/problem/P3383 */
+use std::io::{stdin, Read};
+use zhl_algorithm_template_rs::math::prime::{Sieve, DeterministicMillerRabin};
+
+fn main() {
+    let mut s = String::new();
+    stdin().read_to_string(&mut s).unwrap();
+    let mut it = s.split_whitespace().map(|x| x.parse::<usize>().unwrap());
+    let (n, q) = (it.next().unwrap(), it.next().unwrap());
+    let sieve = Sieve::new(n, &DeterministicMillerRabin::new());
+    let mut out = String::new();
+    for _ in 0..q {
+        let k = it.next().unwrap();
+        out.push_str(&format!("{}\n", sieve.primes()[k - 1]));
+    }
+    print!("{}", out);
+}
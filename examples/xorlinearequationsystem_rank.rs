@@ -0,0 +1,37 @@
+/** Problem link: https://www.luogu.com.cn/problem/P4869 */
+use std::io::{stdin, Read};
+use zhl_algorithm_template_rs::math::linearequation::XorLinearEquationSystem;
+
+fn main() {
+    let mut s = String::new();
+    stdin().read_to_string(&mut s).unwrap();
+    let mut split = s.split_ascii_whitespace().map(|x| x.parse::<u64>().unwrap());
+    let n = split.next().unwrap() as usize;
+
+    let mut system = XorLinearEquationSystem::new(0, 31);
+    for _ in 0..n {
+        let a = split.next().unwrap();
+        let mut equation = system.new_equation();
+        for j in 0..31 {
+            if (a >> j) & 1 == 1 {
+                equation.set_i(j);
+            }
+        }
+        system.insert_basis(equation);
+    }
+
+    let q = split.next().unwrap();
+    let mut equation = system.new_equation();
+    for j in 0..31 {
+        if (q >> j) & 1 == 1 {
+            equation.set_i(j);
+        }
+    }
+    // every representable value appears 2^(n - r) times in the multiset of subset xors
+    let k = (system.get_rank(equation).unwrap() % 10086) as u64;
+    let mut ans = k;
+    for _ in system.count_basis()..n {
+        ans = ans * 2 % 10086;
+    }
+    println!("{}", (ans + 1) % 10086);
+}
@@ -0,0 +1,48 @@
+/** Problem link: https://www.luogu.com.cn/problem/P2447 */
+use std::io::{stdin, Read};
+use zhl_algorithm_template_rs::math::linearequation::{XorLinearEquationSolution, XorLinearEquationSystem};
+
+fn solve_prefix(equations: &[(Vec<u8>, bool)], n: usize, k: usize) -> XorLinearEquationSolution {
+    let mut system = XorLinearEquationSystem::new(k, n);
+    for (i, (a, _)) in equations[..k].iter().enumerate() {
+        for (j, &c) in a.iter().enumerate() {
+            if c == b'1' {
+                system.set_i_j(i, j);
+            }
+        }
+    }
+    let rhs = equations[..k].iter().map(|&(_, b)| b).collect::<Vec<_>>();
+    system.solve(&rhs).unwrap()
+}
+
+fn main() {
+    let mut s = String::new();
+    stdin().read_to_string(&mut s).unwrap();
+    let mut split = s.split_ascii_whitespace();
+    let n = split.next().unwrap().parse::<usize>().unwrap();
+    let m = split.next().unwrap().parse::<usize>().unwrap();
+    let equations = (0..m).map(|_| {
+        let a = split.next().unwrap().as_bytes().to_vec();
+        let b = split.next().unwrap() == "1";
+        (a, b)
+    }).collect::<Vec<_>>();
+
+    if solve_prefix(&equations, n, m).rank() < n {
+        println!("Cannot Determine");
+        return;
+    }
+    let (mut l, mut r) = (n, m);
+    while l < r {
+        let mid = (l + r) / 2;
+        if solve_prefix(&equations, n, mid).rank() == n {
+            r = mid;
+        } else {
+            l = mid + 1;
+        }
+    }
+    let solution = solve_prefix(&equations, n, l);
+    println!("{}", l);
+    for i in 0..n {
+        println!("{}", if solution.solution().get_i(i) { "?y7M#" } else { "Earth" });
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::BTreeSet;
+use zhl_algorithm_template_rs::{math::linearequation::{XorLinearEquationSystem, ZeroOneLinearEquation}, random::generator::{IntGenerator, MT19937_64, Pseudorandom64}};
+
+const N: usize = 8;
+
+fn to_equation(x: u64) -> ZeroOneLinearEquation {
+    let mut equation = ZeroOneLinearEquation::new(N);
+    for j in 0..N {
+        if (x >> j) & 1 == 1 {
+            equation.set_i(j);
+        }
+    }
+    equation
+}
+
+fn span(xs: &[u64]) -> BTreeSet<u64> {
+    let mut set = BTreeSet::from([0]);
+    for &x in xs {
+        let next: Vec<u64> = set.iter().map(|&y| y ^ x).collect();
+        set.extend(next);
+    }
+    set
+}
+
+fn build(xs: &[u64]) -> XorLinearEquationSystem {
+    let mut system = XorLinearEquationSystem::new(0, N);
+    for &x in xs {
+        system.insert_basis(to_equation(x));
+    }
+    system
+}
+
+fn main() {
+    let mut gen = IntGenerator::new(&MT19937_64::new(None));
+    for _ in 0..10000 {
+        let xs: Vec<u64> = (0..gen.gen_range_usize(0..6)).map(|_| gen.gen_range_u64(0..1 << N)).collect();
+        let ys: Vec<u64> = (0..gen.gen_range_usize(0..6)).map(|_| gen.gen_range_u64(0..1 << N)).collect();
+        let (u, v) = (build(&xs), build(&ys));
+        let (su, sv) = (span(&xs), span(&ys));
+
+        let intersection = u.intersection(&v);
+        let expected: BTreeSet<u64> = su.intersection(&sv).copied().collect();
+        assert_eq!(intersection.count_representable(), Some(expected.len() as u128));
+        assert!(expected.iter().all(|&x| intersection.can_represent(to_equation(x))));
+        assert!(intersection.is_subspace_of(&u) && intersection.is_subspace_of(&v));
+
+        assert_eq!(u.is_subspace_of(&v), su.is_subset(&sv));
+        assert_eq!(v.is_subspace_of(&u), sv.is_subset(&su));
+        assert_eq!(u.is_same_subspace(&v), su == sv);
+        assert!(u.union(&v).is_same_subspace(&build(&[xs.clone(), ys.clone()].concat())));
+
+        let mut shuffled = xs.clone();
+        shuffled.reverse();
+        shuffled.push(xs.iter().fold(0, |a, &b| a ^ b));
+        assert!(u.is_same_subspace(&build(&shuffled)));
+    }
+
+    println!("subspace test passed!");
+}
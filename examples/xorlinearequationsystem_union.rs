@@ -0,0 +1,64 @@
+/** Problem link: https://www.luogu.com.cn/problem/P4839 */
+use std::io::{stdin, Read, Write, stdout, BufWriter};
+use zhl_algorithm_template_rs::math::linearequation::XorLinearEquationSystem;
+
+fn update(tree: &mut [XorLinearEquationSystem], node: usize, l: usize, r: usize, k: usize, x: usize) {
+    let mut equation = tree[node].new_equation();
+    for j in 0..31 {
+        if (x >> j) & 1 == 1 {
+            equation.set_i(j);
+        }
+    }
+    tree[node].insert_basis(equation);
+    if l == r {
+        return;
+    }
+    let mid = (l + r) / 2;
+    if k <= mid {
+        update(tree, node * 2, l, mid, k, x);
+    } else {
+        update(tree, node * 2 + 1, mid + 1, r, k, x);
+    }
+}
+
+fn query(tree: &[XorLinearEquationSystem], node: usize, l: usize, r: usize, ql: usize, qr: usize, ans: &mut XorLinearEquationSystem) {
+    if ans.is_full_basis() {
+        return;
+    }
+    if ql <= l && r <= qr {
+        ans.merge(&tree[node]);
+        return;
+    }
+    let mid = (l + r) / 2;
+    if ql <= mid {
+        query(tree, node * 2, l, mid, ql, qr, ans);
+    }
+    if qr > mid {
+        query(tree, node * 2 + 1, mid + 1, r, ql, qr, ans);
+    }
+}
+
+fn main() {
+    let mut s = String::new();
+    stdin().read_to_string(&mut s).unwrap();
+    let mut split = s.split_ascii_whitespace().map(|x| x.parse::<usize>().unwrap());
+    let n = split.next().unwrap();
+    let m = split.next().unwrap();
+
+    let mut tree = vec![XorLinearEquationSystem::new(0, 31); 4 * m];
+    let mut out = BufWriter::new(stdout());
+    for _ in 0..n {
+        let op = split.next().unwrap();
+        let a = split.next().unwrap();
+        let b = split.next().unwrap();
+        if op == 1 {
+            update(&mut tree, 1, 1, m, a, b);
+        } else {
+            let mut ans = XorLinearEquationSystem::new(0, 31);
+            query(&tree, 1, 1, m, a, b, &mut ans);
+            let mx = ans.get_max();
+            let ans = (0..31).filter(|&j| mx.get_i(j)).map(|j| 1 << j).sum::<usize>();
+            writeln!(out, "{}", ans).unwrap();
+        }
+    }
+}
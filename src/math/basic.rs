@@ -1,112 +1,549 @@
+use crate::math::integer::UnsignedInt;
 
-/** Return (`a` + `b`) mod `c`*/
+/** Return (`a` + `b`) mod `c` for any `UnsignedInt`. `a` and `b` must be smaller than `c`. */
 #[inline(always)]
-pub fn add_mod_u32(a: &u32, b: &u32, c: &u32) -> u32 {
-    if *a >= *c - *b {
-        *a - (*c - *b)
+pub fn add_mod<T: UnsignedInt>(a: T, b: T, c: T) -> T {
+    if a >= c - b {
+        a - (c - b)
     } else {
-        *a + *b
+        a + b
     }
 }
 
-/** Return (`a` + `b`) mod `c`*/
+/** Return (`a` - `b`) mod `c` for any `UnsignedInt`. `a` and `b` must be smaller than `c`. */
 #[inline(always)]
-pub fn add_mod_u64(a: &u64, b: &u64, c: &u64) -> u64 {
-    if *a >= *c - *b {
-        *a - (*c - *b)
+pub fn sub_mod<T: UnsignedInt>(a: T, b: T, c: T) -> T {
+    if a >= b {
+        a - b
     } else {
-        *a + *b
+        a + (c - b)
+    }
+}
+
+/** Return (`a` * `b`) mod `c` for any `UnsignedInt`. */
+#[inline(always)]
+pub fn mul_mod<T: UnsignedInt>(a: T, b: T, c: T) -> T {
+    a.mul_mod(b, c)
+}
+
+/** `pow_mod` is an algorithm to compute $a^b \mod c$ in $O(\log(b))$ time for any `UnsignedInt`. */
+pub fn pow_mod<T: UnsignedInt>(a: T, mut b: T, c: T) -> T {
+    let mut ans = T::ONE % c;
+    let mut a = a % c;
+    while b > T::ZERO {
+        if b & T::ONE == T::ONE {
+            ans = mul_mod(ans, a, c);
+        }
+        a = mul_mod(a, a, c);
+        b >>= 1;
+    }
+    ans
+}
+
+/** `gcd` is an algorithm to compute the greatest common divisor of two integers $a$ and $b$ in $O(\log (a+b))$ for any `UnsignedInt`. */
+pub fn gcd<T: UnsignedInt>(mut a: T, mut b: T) -> T {
+    while b > T::ZERO {
+        a %= b;
+        std::mem::swap(&mut a, &mut b);
     }
+    a
+}
+
+/** `lcm` computes the least common multiple of $a$ and $b$ for any `UnsignedInt`. Return `None` if it overflows. */
+pub fn lcm<T: UnsignedInt>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/** Return (`a` + `b`) mod `c`*/
+#[inline(always)]
+pub fn add_mod_u32(a: &u32, b: &u32, c: &u32) -> u32 {
+    add_mod(*a, *b, *c)
 }
+
+/** Return (`a` + `b`) mod `c`*/
+#[inline(always)]
+pub fn add_mod_u64(a: &u64, b: &u64, c: &u64) -> u64 {
+    add_mod(*a, *b, *c)
+}
+
 /** Return (`a` - `b`) mod `c`*/
 #[inline(always)]
 pub fn sub_mod_u32(a: &u32, b: &u32, c: &u32) -> u32 {
-    if *a >= *b {
-        *a - *b
-    } else {
-        *a + (*c - *b)
-    }
+    sub_mod(*a, *b, *c)
 }
 
 /** Return (`a` - `b`) mod `c`*/
 #[inline(always)]
 pub fn sub_mod_u64(a: &u64, b: &u64, c: &u64) -> u64 {
-    if *a >= *b {
-        *a - *b
-    } else {
-        *a + (*c - *b)
-    }
+    sub_mod(*a, *b, *c)
 }
 
 /** Return (`a` * `b`) mod `c`*/
 #[inline(always)]
 pub fn mul_mod_u32(a: &u32, b: &u32, c: &u32) -> u32 {
-    ((*a as u64) * (*b as u64) % (*c as u64)) as u32
+    mul_mod(*a, *b, *c)
 }
 
 /** Return (`a` * `b`) mod `c`*/
 #[inline(always)]
 pub fn mul_mod_u64(a: &u64, b: &u64, c: &u64) -> u64 {
-    ((*a as u128) * (*b as u128) % (*c as u128)) as u64
+    mul_mod(*a, *b, *c)
 }
 
 /** `pow_mod_u32` is an algorithm to compute $a^b \mod c$ in $O(\log(b))$ time*/
 pub fn pow_mod_u32(a: &u32, b: &u32, c: &u32) -> u32 {
-    let mut ans = 1u64;
-    let mut a = *a as u64;
-    let mut b = *b as u64;
-    let c = *c as u64;
-    while b > 0 {
-        if b & 1 == 1 {
-            ans = (ans * a) % c;
-        }
-        a = (a * a) % c;
-        b >>= 1;
-    }
-    ans as u32
+    pow_mod(*a, *b, *c)
 }
 
-/** `pow_mod_u64` is an algorithm to compute $a^b \mod c$ in $O(\log(b))$ time*/
+/** `pow_mod_u64` is an algorithm to compute $a^b \mod c$ in $O(\log(b))$ time. The multiplications are done by `Barrett64`. */
 pub fn pow_mod_u64(a: &u64, b: &u64, c: &u64) -> u64 {
-    let mut ans = 1u128;
-    let mut a = *a as u128;
-    let mut b = *b as u128;
-    let c = *c as u128;
-    while b > 0 {
-        if b & 1 == 1 {
-            ans = (ans * a) % c;
-        }
-        a = (a * a) % c;
-        b >>= 1;
-    }
-    ans as u64
+    Barrett64::new(*c).pow(*a % *c, *b)
 }
 
 /** `gcd_u32` is an algorithm to compute the greatest common divisor of two integers $a$ and $b$ in $O(\log (a+b))$ */
-pub fn gcd_u32(mut a: u32, mut b: u32) -> u32 {
-    while b > 0 {
-        a %= b;
-        std::mem::swap(&mut a, &mut b);
-    }
-    a
+pub fn gcd_u32(a: u32, b: u32) -> u32 {
+    gcd(a, b)
 }
 
 /** `gcd_u64` is an algorithm to compute the greatest common divisor of two integers $a$ and $b$ in $O(\log (a+b))$ */
-pub fn gcd_u64(mut a: u64, mut b: u64) -> u64 {
-    while b > 0 {
-        a %= b;
-        std::mem::swap(&mut a, &mut b);
-    }
-    a
+pub fn gcd_u64(a: u64, b: u64) -> u64 {
+    gcd(a, b)
 }
 
 /** `gcd_u128` is an algorithm to compute the greatest common divisor of two integers $a$ and $b$ in $O(\log (a+b))$ */
-pub fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
-    while b > 0 {
-        a %= b;
-        std::mem::swap(&mut a, &mut b);
+pub fn gcd_u128(a: u128, b: u128) -> u128 {
+    gcd(a, b)
+}
+
+/** `lcm_u32` computes the least common multiple of $a$ and $b$. Return `None` if it overflows. */
+pub fn lcm_u32(a: u32, b: u32) -> Option<u32> {
+    lcm(a, b)
+}
+
+/** `lcm_u64` computes the least common multiple of $a$ and $b$. Return `None` if it overflows. */
+pub fn lcm_u64(a: u64, b: u64) -> Option<u64> {
+    lcm(a, b)
+}
+
+/** `lcm_u128` computes the least common multiple of $a$ and $b$. Return `None` if it overflows. */
+pub fn lcm_u128(a: u128, b: u128) -> Option<u128> {
+    lcm(a, b)
+}
+
+/** `exgcd_i64` is the extended Euclidean algorithm. It returns $(g, x, y)$ such that $ax + by = g = \gcd(a, b) \geq 0$ in $O(\log (|a|+|b|))$ time. */
+pub fn exgcd_i64(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1i64, 0i64);
+    let (mut old_y, mut y) = (0i64, 1i64);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/** `exgcd_i128` is the extended Euclidean algorithm. It returns $(g, x, y)$ such that $ax + by = g = \gcd(a, b) \geq 0$ in $O(\log (|a|+|b|))$ time. */
+pub fn exgcd_i128(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1i128, 0i128);
+    let (mut old_y, mut y) = (0i128, 1i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/** Return $a^{-1} \mod m$, or `None` if `a` and `m` are not coprime. `m` must be positive. */
+pub fn inv_mod_u32(a: &u32, m: &u32) -> Option<u32> {
+    assert!(*m > 0, "The modulus must be positive.");
+    let (g, x, _) = exgcd_i64(*a as i64, *m as i64);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(*m as i64) as u32)
+}
+
+/** Return $a^{-1} \mod m$, or `None` if `a` and `m` are not coprime. `m` must be positive. */
+pub fn inv_mod_u64(a: &u64, m: &u64) -> Option<u64> {
+    assert!(*m > 0, "The modulus must be positive.");
+    let (g, x, _) = exgcd_i128(*a as i128, *m as i128);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(*m as i128) as u64)
+}
+
+/** `crt_u64` solves the system of congruences $x \equiv r_i \pmod {m_i}$ given as pairs `(r_i, m_i)` where the moduli need not be pairwise coprime. It returns `(r, m)` such that the solutions are exactly $x \equiv r \pmod m$ with $0 \leq r < m$ and $m = \mathrm{lcm}(m_i)$. It returns `None` if the system is inconsistent or if $m$ does not fit in 64 bits. All moduli must be positive. It takes $O(k \log M)$ time for $k$ congruences. */
+pub fn crt_u64(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let mut r = 0u64;
+    let mut m = 1u64;
+    for &(r2, m2) in congruences {
+        assert!(m2 > 0, "The modulus must be positive.");
+        let g = gcd_u64(m, m2);
+        let diff = sub_mod_u64(&(r2 % m2), &(r % m2), &m2);
+        if !diff.is_multiple_of(g) {
+            return None;
+        }
+        let m2g = m2 / g;
+        let inv = inv_mod_u64(&(m / g % m2g), &m2g).unwrap();
+        let k = mul_mod_u64(&(diff / g % m2g), &inv, &m2g);
+        let new_m = m.checked_mul(m2g)?;
+        r = (r as u128 + m as u128 * k as u128) as u64;
+        m = new_m;
+    }
+    Some((r, m))
+}
+
+/** Return the high 128 bits of the 256-bit product `a` * `b`. */
+#[inline(always)]
+pub fn mul_high_u128(a: u128, b: u128) -> u128 {
+    let (a1, a0) = (a >> 64, a & 0xffffffffffffffff);
+    let (b1, b0) = (b >> 64, b & 0xffffffffffffffff);
+    let lo = a0 * b0;
+    let mid1 = a0 * b1;
+    let mid2 = a1 * b0;
+    let carry = ((lo >> 64) + (mid1 & 0xffffffffffffffff) + (mid2 & 0xffffffffffffffff)) >> 64;
+    a1 * b1 + (mid1 >> 64) + (mid2 >> 64) + carry
+}
+
+/** `Barrett64` is a context of Barrett reduction for a fixed 64-bit modulus $m$. It precomputes $\lfloor (2^{128}-1)/m \rfloor$ once so that each reduction of a 128-bit integer takes a few multiplications instead of a 128-bit division. */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Barrett64 {
+    m: u64,
+    im: u128,
+}
+
+impl Barrett64 {
+
+    /** New a `Barrett64` with modulus `m`. `m` must be positive. */
+    pub const fn new(m: u64) -> Self {
+        assert!(m > 0, "The modulus must be positive.");
+        Barrett64 { m, im: u128::MAX / m as u128 }
+    }
+
+    /** Return the modulus. */
+    #[inline(always)]
+    pub fn modulus(&self) -> u64 {
+        self.m
+    }
+
+    /** Return `z` mod `m`. */
+    #[inline(always)]
+    pub fn reduce(&self, z: u128) -> u64 {
+        let q = mul_high_u128(z, self.im);
+        let m = self.m as u128;
+        let mut r = z - q * m;
+        while r >= m {
+            r -= m;
+        }
+        r as u64
+    }
+
+    /** Return (`a` * `b`) mod `m`. */
+    #[inline(always)]
+    pub fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+
+    /** Return $a^b \mod m$ in $O(\log(b))$ time. */
+    pub fn pow(&self, mut a: u64, mut b: u64) -> u64 {
+        let mut ans = self.reduce(1);
+        while b > 0 {
+            if b & 1 == 1 {
+                ans = self.mul(ans, a);
+            }
+            a = self.mul(a, a);
+            b >>= 1;
+        }
+        ans
+    }
+}
+
+/** `Montgomery32` is a context of Montgomery multiplication for a fixed odd 32-bit modulus $n$ with $R = 2^{32}$. Values in the Montgomery form $aR \mod n$ are multiplied without any division. */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Montgomery32 {
+    n: u32,
+    n_inv: u32, // n * n_inv = 1 mod 2^32
+    r2: u32,    // 2^64 mod n
+}
+
+impl Montgomery32 {
+
+    /** New a `Montgomery32` with modulus `n`. `n` must be odd. */
+    pub fn new(n: u32) -> Self {
+        assert!(n & 1 == 1, "The modulus of Montgomery multiplication must be odd.");
+        let mut n_inv = n;
+        for _ in 0..4 {
+            n_inv = n_inv.wrapping_mul(2u32.wrapping_sub(n.wrapping_mul(n_inv)));
+        }
+        let r2 = ((1u128 << 64) % n as u128) as u32;
+        Montgomery32 { n, n_inv, r2 }
+    }
+
+    /** Return the modulus. */
+    #[inline(always)]
+    pub fn modulus(&self) -> u32 {
+        self.n
+    }
+
+    /** Return $TR^{-1} \mod n$ for $T < nR$. */
+    #[inline(always)]
+    pub fn reduce(&self, t: u64) -> u32 {
+        let m = (t as u32).wrapping_mul(self.n_inv);
+        let mn = ((m as u64 * self.n as u64) >> 32) as u32;
+        let t_hi = (t >> 32) as u32;
+        if t_hi < mn {
+            t_hi.wrapping_sub(mn).wrapping_add(self.n)
+        } else {
+            t_hi - mn
+        }
+    }
+
+    /** Transform `a` into the Montgomery form. */
+    #[inline(always)]
+    pub fn to_mont(&self, a: u32) -> u32 {
+        self.reduce(a as u64 * self.r2 as u64)
+    }
+
+    /** Transform `a` from the Montgomery form back. */
+    #[inline(always)]
+    pub fn from_mont(&self, a: u32) -> u32 {
+        self.reduce(a as u64)
+    }
+
+    /** Return the Montgomery form of `1`. */
+    #[inline(always)]
+    pub fn one(&self) -> u32 {
+        self.to_mont(1)
+    }
+
+    /** Return (`a` + `b`) mod `n` where both are in the Montgomery form. */
+    #[inline(always)]
+    pub fn add(&self, a: u32, b: u32) -> u32 {
+        add_mod_u32(&a, &b, &self.n)
+    }
+
+    /** Return (`a` - `b`) mod `n` where both are in the Montgomery form. */
+    #[inline(always)]
+    pub fn sub(&self, a: u32, b: u32) -> u32 {
+        sub_mod_u32(&a, &b, &self.n)
+    }
+
+    /** Return the Montgomery product of `a` and `b` where both are in the Montgomery form. */
+    #[inline(always)]
+    pub fn mul(&self, a: u32, b: u32) -> u32 {
+        self.reduce(a as u64 * b as u64)
+    }
+
+    /** Return $a^b$ where `a` and the result are in the Montgomery form. */
+    pub fn pow(&self, mut a: u32, mut b: u64) -> u32 {
+        let mut ans = self.one();
+        while b > 0 {
+            if b & 1 == 1 {
+                ans = self.mul(ans, a);
+            }
+            a = self.mul(a, a);
+            b >>= 1;
+        }
+        ans
+    }
+}
+
+/** `Montgomery64` is a context of Montgomery multiplication for a fixed odd 64-bit modulus $n$ with $R = 2^{64}$. Values in the Montgomery form $aR \mod n$ are multiplied without any 128-bit division. */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Montgomery64 {
+    n: u64,
+    n_inv: u64, // n * n_inv = 1 mod 2^64
+    r2: u64,    // 2^128 mod n
+}
+
+impl Montgomery64 {
+
+    /** New a `Montgomery64` with modulus `n`. `n` must be odd. */
+    pub fn new(n: u64) -> Self {
+        assert!(n & 1 == 1, "The modulus of Montgomery multiplication must be odd.");
+        let mut n_inv = n;
+        for _ in 0..5 {
+            n_inv = n_inv.wrapping_mul(2u64.wrapping_sub(n.wrapping_mul(n_inv)));
+        }
+        let r = (1u128 << 64) % n as u128;
+        let r2 = (r * r % n as u128) as u64;
+        Montgomery64 { n, n_inv, r2 }
+    }
+
+    /** Return the modulus. */
+    #[inline(always)]
+    pub fn modulus(&self) -> u64 {
+        self.n
+    }
+
+    /** Return $TR^{-1} \mod n$ for $T < nR$. */
+    #[inline(always)]
+    pub fn reduce(&self, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(self.n_inv);
+        let mn = ((m as u128 * self.n as u128) >> 64) as u64;
+        let t_hi = (t >> 64) as u64;
+        if t_hi < mn {
+            t_hi.wrapping_sub(mn).wrapping_add(self.n)
+        } else {
+            t_hi - mn
+        }
+    }
+
+    /** Transform `a` into the Montgomery form. */
+    #[inline(always)]
+    pub fn to_mont(&self, a: u64) -> u64 {
+        self.reduce(a as u128 * self.r2 as u128)
+    }
+
+    /** Transform `a` from the Montgomery form back. */
+    #[inline(always)]
+    pub fn from_mont(&self, a: u64) -> u64 {
+        self.reduce(a as u128)
+    }
+
+    /** Return the Montgomery form of `1`. */
+    #[inline(always)]
+    pub fn one(&self) -> u64 {
+        self.to_mont(1)
+    }
+
+    /** Return (`a` + `b`) mod `n` where both are in the Montgomery form. */
+    #[inline(always)]
+    pub fn add(&self, a: u64, b: u64) -> u64 {
+        add_mod_u64(&a, &b, &self.n)
+    }
+
+    /** Return (`a` - `b`) mod `n` where both are in the Montgomery form. */
+    #[inline(always)]
+    pub fn sub(&self, a: u64, b: u64) -> u64 {
+        sub_mod_u64(&a, &b, &self.n)
+    }
+
+    /** Return the Montgomery product of `a` and `b` where both are in the Montgomery form. */
+    #[inline(always)]
+    pub fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+
+    /** Return $a^b$ where `a` and the result are in the Montgomery form. */
+    pub fn pow(&self, mut a: u64, mut b: u64) -> u64 {
+        let mut ans = self.one();
+        while b > 0 {
+            if b & 1 == 1 {
+                ans = self.mul(ans, a);
+            }
+            a = self.mul(a, a);
+            b >>= 1;
+        }
+        ans
+    }
+}
+
+/** `Montgomery128` is a context of Montgomery multiplication for a fixed odd 128-bit modulus $n$ with $R = 2^{128}$. The 256-bit products are split into halves by `mul_high_u128` and `wrapping_mul`, so no 256-bit division is needed. */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Montgomery128 {
+    n: u128,
+    n_inv: u128, // n * n_inv = 1 mod 2^128
+    r2: u128,    // 2^256 mod n
+}
+
+impl Montgomery128 {
+
+    /** New a `Montgomery128` with modulus `n`. `n` must be odd. */
+    pub fn new(n: u128) -> Self {
+        assert!(n & 1 == 1, "The modulus of Montgomery multiplication must be odd.");
+        let mut n_inv = n;
+        for _ in 0..6 {
+            n_inv = n_inv.wrapping_mul(2u128.wrapping_sub(n.wrapping_mul(n_inv)));
+        }
+        let r = (u128::MAX % n + 1) % n;
+        let r2 = mul_mod(r, r, n);
+        Montgomery128 { n, n_inv, r2 }
+    }
+
+    /** Return the modulus. */
+    #[inline(always)]
+    pub fn modulus(&self) -> u128 {
+        self.n
+    }
+
+    /** Return $TR^{-1} \mod n$ for $T = $ `hi` $\cdot 2^{128} +$ `lo` $< nR$. */
+    #[inline(always)]
+    pub fn reduce(&self, hi: u128, lo: u128) -> u128 {
+        let m = lo.wrapping_mul(self.n_inv);
+        let mn = mul_high_u128(m, self.n);
+        if hi < mn {
+            hi.wrapping_sub(mn).wrapping_add(self.n)
+        } else {
+            hi - mn
+        }
+    }
+
+    /** Transform `a` into the Montgomery form. */
+    #[inline(always)]
+    pub fn to_mont(&self, a: u128) -> u128 {
+        self.mul(a % self.n, self.r2)
+    }
+
+    /** Transform `a` from the Montgomery form back. */
+    #[inline(always)]
+    pub fn from_mont(&self, a: u128) -> u128 {
+        self.reduce(0, a)
+    }
+
+    /** Return the Montgomery form of `1`. */
+    #[inline(always)]
+    pub fn one(&self) -> u128 {
+        self.to_mont(1)
+    }
+
+    /** Return (`a` + `b`) mod `n` where both are in the Montgomery form. */
+    #[inline(always)]
+    pub fn add(&self, a: u128, b: u128) -> u128 {
+        add_mod(a, b, self.n)
+    }
+
+    /** Return (`a` - `b`) mod `n` where both are in the Montgomery form. */
+    #[inline(always)]
+    pub fn sub(&self, a: u128, b: u128) -> u128 {
+        sub_mod(a, b, self.n)
+    }
+
+    /** Return the Montgomery product of `a` and `b` where both are in the Montgomery form. */
+    #[inline(always)]
+    pub fn mul(&self, a: u128, b: u128) -> u128 {
+        self.reduce(mul_high_u128(a, b), a.wrapping_mul(b))
+    }
+
+    /** Return $a^b$ where `a` and the result are in the Montgomery form. */
+    pub fn pow(&self, mut a: u128, mut b: u128) -> u128 {
+        let mut ans = self.one();
+        while b > 0 {
+            if b & 1 == 1 {
+                ans = self.mul(ans, a);
+            }
+            a = self.mul(a, a);
+            b >>= 1;
+        }
+        ans
     }
-    a
 }
 
 pub mod from_to {
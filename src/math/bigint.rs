@@ -1,50 +1,1114 @@
-// use std::ops::{Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, DivAssign, Mul, MulAssign, Neg, RangeBounds, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign};
-// pub trait Integer: Sized + Add{
-// }
-
-
-// pub struct BinaryInt {
-//     neg: bool,
-//     v: Vec<u64>,
-// }
-
-// impl BinaryInt {
-//     pub fn zero() -> Self {
-//         BinaryInt { neg: false, v: vec![0] }
-//     }
-//     pub fn from_i64(v: i64) -> Self {
-//         if v >= 0 {
-//             return BinaryInt { neg: false, v: vec![v.try_into().unwrap()] };
-//         }
-//         BinaryInt { neg: true, v: vec![(-v).try_into().unwrap()] }
-//     }
-//     pub fn from_i128(v: u64) -> Self {
-//         todo!();
-//     }
-//     pub fn clean(&mut self) {
-//         while self.v.len() > 1 && self.v[self.v.len() - 1] == 0 {
-//             self.v.pop();
-//         }
-//         if self.neg && self.is_zero() {
-//             self.neg = false;
-//         }
-//     }
-//     pub fn is_zero(&self) -> bool {
-//         self.v.len() == 1 && self.v[0] == 0
-//     }
-//     pub fn bits(&self) -> usize {
-//         todo!();
-//     }
-// }
-
-// impl Integer for BinaryInt {
-
-// }
-
-// impl Add for BinaryInt {
-//     type Output = Self;
-
-//     fn add(self,  other: Self) -> Self {
-
-//     }
-// }
\ No newline at end of file
+use std::{cmp::Ordering, fmt, iter::{Product, Sum}, str::FromStr};
+use crate::math::convolution::convolution_u64;
+use std::ops::{Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, DivAssign, Mul, MulAssign, Neg, Not, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign};
+
+/** `BinaryInt` is a signed arbitrary-precision integer stored as a sign and a magnitude of little-endian 64-bit limbs. It supports:
+    + `+`, `-`, `*`, `/`, `%` (and `divrem`) where the division truncates toward zero like the primitive integers. The multiplication switches from the schoolbook method to Karatsuba ($O(n^{1.59})$) and then to NTT ($O(n \log n)$) as the operands grow, and the division of large operands is done by Newton's iteration in the time of a constant number of multiplications.
+    + `<<`, `>>` where `>>` rounds toward negative infinity, and `&`, `|`, `^`, `!` with the two's complement semantics of an infinitely sign-extended integer. ($O(n)$ time)
+    + comparison, conversion from/to all primitive integers, parsing by `FromStr` (decimal, or hexadecimal with a `0x` prefix) and printing by `Display`, `LowerHex` and `UpperHex`. Large numbers are converted between bases by divide and conquer, so printing $10^6$ decimal digits is not quadratic.
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BinaryInt {
+    neg: bool,
+    v: Vec<u64>, // the magnitude, v.len() >= 1 and v[v.len() - 1] != 0 unless the value is zero
+}
+
+/* Operations on magnitudes. All of them accept slices with leading zero limbs and return trimmed vectors. */
+
+fn trim(v: &mut Vec<u64>) {
+    while v.len() > 1 && v[v.len() - 1] == 0 {
+        v.pop();
+    }
+    if v.is_empty() {
+        v.push(0);
+    }
+}
+
+fn significant_len(a: &[u64]) -> usize {
+    let mut n = a.len();
+    while n > 0 && a[n - 1] == 0 {
+        n -= 1;
+    }
+    n
+}
+
+fn cmp_mag(a: &[u64], b: &[u64]) -> Ordering {
+    let (n, m) = (significant_len(a), significant_len(b));
+    if n != m {
+        return n.cmp(&m);
+    }
+    for i in (0..n).rev() {
+        if a[i] != b[i] {
+            return a[i].cmp(&b[i]);
+        }
+    }
+    Ordering::Equal
+}
+
+fn add_mag(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut ret = Vec::with_capacity(a.len() + 1);
+    let mut carry = false;
+    for i in 0..a.len() {
+        let (s, c1) = a[i].overflowing_add(if i < b.len() { b[i] } else { 0 });
+        let (s, c2) = s.overflowing_add(carry as u64);
+        ret.push(s);
+        carry = c1 || c2;
+    }
+    ret.push(carry as u64);
+    trim(&mut ret);
+    ret
+}
+
+/* `a` must be no less than `b`. */
+fn sub_mag(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut ret = Vec::with_capacity(a.len());
+    let mut borrow = false;
+    for i in 0..a.len() {
+        let (s, b1) = a[i].overflowing_sub(if i < b.len() { b[i] } else { 0 });
+        let (s, b2) = s.overflowing_sub(borrow as u64);
+        ret.push(s);
+        borrow = b1 || b2;
+    }
+    debug_assert!(!borrow);
+    trim(&mut ret);
+    ret
+}
+
+const KARATSUBA_THRESHOLD: usize = 32;
+const NTT_THRESHOLD: usize = 1024;
+const NTT_MAX_LIMBS: usize = 1 << 22;
+const NEWTON_THRESHOLD: usize = 128;
+const DC_CONVERSION_THRESHOLD: usize = 32;
+
+/* Return `a` * `b`, choosing the schoolbook method, Karatsuba or NTT by the size of the smaller operand. */
+fn mul_mag(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (n, m) = (significant_len(a), significant_len(b));
+    if n == 0 || m == 0 {
+        return vec![0];
+    }
+    let (a, b) = (&a[..n], &b[..m]);
+    let small = n.min(m);
+    let mut ret = if small < KARATSUBA_THRESHOLD {
+        mul_mag_basecase(a, b)
+    } else if small < NTT_THRESHOLD || n + m > NTT_MAX_LIMBS {
+        mul_mag_karatsuba(a, b)
+    } else {
+        mul_mag_ntt(a, b)
+    };
+    trim(&mut ret);
+    ret
+}
+
+fn mul_mag_basecase(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (n, m) = (a.len(), b.len());
+    let mut ret = vec![0u64; n + m];
+    for i in 0..n {
+        let mut carry = 0u128;
+        for j in 0..m {
+            let t = a[i] as u128 * b[j] as u128 + ret[i + j] as u128 + carry;
+            ret[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        ret[i + m] = carry as u64;
+    }
+    ret
+}
+
+/* Add `src` into `dst` in place. The sum must fit in `dst`. */
+fn add_into(dst: &mut [u64], src: &[u64]) {
+    let mut carry = false;
+    let mut i = 0;
+    while i < src.len() || carry {
+        let (t, c1) = dst[i].overflowing_add(if i < src.len() { src[i] } else { 0 });
+        let (t, c2) = t.overflowing_add(carry as u64);
+        dst[i] = t;
+        carry = c1 || c2;
+        i += 1;
+    }
+}
+
+fn mul_mag_karatsuba(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let (n, m) = (a.len(), b.len());
+    let mut ret = vec![0u64; n + m];
+    if n >= 2 * m {
+        for (i, chunk) in a.chunks(m).enumerate() {
+            add_into(&mut ret[i * m..], &mul_mag(chunk, b));
+        }
+        return ret;
+    }
+    let h = n.div_ceil(2);
+    let (a0, a1) = a.split_at(h);
+    let (b0, b1) = b.split_at(h);
+    let z0 = mul_mag(a0, b0);
+    let z2 = mul_mag(a1, b1);
+    let z1 = mul_mag(&add_mag(a0, a1), &add_mag(b0, b1));
+    let z1 = sub_mag(&sub_mag(&z1, &z0), &z2);
+    add_into(&mut ret, &z0);
+    add_into(&mut ret[h..], &z1);
+    add_into(&mut ret[2 * h..], &z2);
+    ret
+}
+
+/* Multiply by `convolution_u64` on 32-bit pieces. With at most `NTT_MAX_LIMBS` limbs in total, the shorter operand has at most $2^{22}$ pieces, so every coefficient is below $2^{22} \cdot 2^{64} = 2^{86}$, which is smaller than the product of the three primes. */
+fn mul_mag_ntt(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let split = |x: &[u64]| x.iter().flat_map(|&v| [v & 0xffffffff, v >> 32]).collect::<Vec<u64>>();
+    let c = convolution_u64(&split(a), &split(b));
+    let mut ret = vec![0u64; a.len() + b.len()];
+    let mut carry = 0u128;
+    for i in 0..ret.len() * 2 {
+        let t = carry + if i < c.len() { c[i] } else { 0 };
+        ret[i / 2] |= ((t & 0xffffffff) as u64) << (32 * (i % 2));
+        carry = t >> 32;
+    }
+    ret
+}
+
+fn bits_mag(a: &[u64]) -> usize {
+    let n = significant_len(a);
+    if n == 0 {
+        return 0;
+    }
+    n * 64 - a[n - 1].leading_zeros() as usize
+}
+
+/* Return (`a` / `d`, `a` % `d`) for a single limb `d > 0`. */
+fn divrem_mag_limb(a: &[u64], d: u64) -> (Vec<u64>, u64) {
+    let mut q = vec![0u64; a.len()];
+    let mut r = 0u128;
+    for i in (0..a.len()).rev() {
+        let cur = r << 64 | a[i] as u128;
+        q[i] = (cur / d as u128) as u64;
+        r = cur % d as u128;
+    }
+    trim(&mut q);
+    (q, r as u64)
+}
+
+fn shl_mag(a: &[u64], k: usize) -> Vec<u64> {
+    let (limbs, bits) = (k / 64, (k % 64) as u32);
+    let mut ret = vec![0u64; limbs];
+    if bits == 0 {
+        ret.extend_from_slice(a);
+    } else {
+        let mut carry = 0u64;
+        for &x in a {
+            ret.push(x << bits | carry);
+            carry = x >> (64 - bits);
+        }
+        ret.push(carry);
+    }
+    trim(&mut ret);
+    ret
+}
+
+fn shr_mag(a: &[u64], k: usize) -> Vec<u64> {
+    let (limbs, bits) = (k / 64, (k % 64) as u32);
+    if limbs >= a.len() {
+        return vec![0];
+    }
+    let mut ret = Vec::with_capacity(a.len() - limbs);
+    for i in limbs..a.len() {
+        let hi = if bits == 0 || i + 1 >= a.len() { 0 } else { a[i + 1] << (64 - bits) };
+        ret.push(a[i] >> bits | hi);
+    }
+    trim(&mut ret);
+    ret
+}
+
+/* Return (`a` / `b`, `a` % `b`), using Newton's iteration when both the divisor and the quotient are large. `b` must be non-zero. */
+fn divrem_mag(a: &[u64], b: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let (na, nb) = (significant_len(a), significant_len(b));
+    if nb >= NEWTON_THRESHOLD && na >= nb + NEWTON_THRESHOLD {
+        divrem_mag_newton(&a[..na], &b[..nb])
+    } else {
+        divrem_mag_basecase(a, b)
+    }
+}
+
+/* Return an approximation of $\lfloor 2^{l+k} / b \rfloor$ where $l$ is the number of bits of `b`, with an error of a few units. The precision is doubled by Newton's iteration $x \to x(2 - bx)$ on the top bits of `b`. */
+fn recip_mag(b: &[u64], k: usize) -> Vec<u64> {
+    let l = bits_mag(b);
+    let t = l.min(k + 64);
+    let bt = shr_mag(b, l - t);
+    if k <= 64 * NEWTON_THRESHOLD {
+        return divrem_mag_basecase(&shl_mag(&[1], t + k), &bt).0;
+    }
+    let h = k / 2 + 32;
+    let x = recip_mag(b, h);
+    let x2b = mul_mag(&mul_mag(&x, &x), &bt);
+    sub_mag(&shl_mag(&x, k - h + 1), &shr_mag(&x2b, 2 * h + t - k))
+}
+
+/* Return (`a` / `b`, `a` % `b`) by multiplying `a` with the reciprocal from `recip_mag` and correcting the quotient. */
+fn divrem_mag_newton(a: &[u64], b: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let (n, l) = (bits_mag(a), bits_mag(b));
+    if n < l {
+        return (vec![0], a.to_vec());
+    }
+    let k = n - l + 1 + 32;
+    divrem_mag_recip(a, b, &recip_mag(b, k), k)
+}
+
+/* Return (`a` / `b`, `a` % `b`) given the reciprocal `r` $= $ `recip_mag(b, k)`, where $k$ must be larger than the difference of the numbers of bits of `a` and `b`, so that one reciprocal can be reused for many dividends. */
+fn divrem_mag_recip(a: &[u64], b: &[u64], r: &[u64], k: usize) -> (Vec<u64>, Vec<u64>) {
+    let l = bits_mag(b);
+    // dropping the lowest l - 1 bits of `a` changes the quotient by at most 1, which is corrected below
+    let mut q = shr_mag(&mul_mag(&shr_mag(a, l - 1), r), k + 1);
+    let mut qb = mul_mag(&q, b);
+    while cmp_mag(&qb, a) == Ordering::Greater {
+        q = sub_mag(&q, &[1]);
+        qb = sub_mag(&qb, b);
+    }
+    let mut rem = sub_mag(a, &qb);
+    while cmp_mag(&rem, b) != Ordering::Less {
+        q = add_mag(&q, &[1]);
+        rem = sub_mag(&rem, b);
+    }
+    (q, rem)
+}
+
+/* Return (`a` / `b`, `a` % `b`) by Knuth's algorithm D in $O(nm)$ time. `b` must be non-zero. */
+fn divrem_mag_basecase(a: &[u64], b: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let (na, nb) = (significant_len(a), significant_len(b));
+    assert!(nb > 0, "Division by zero.");
+    if cmp_mag(a, b) == Ordering::Less {
+        let mut r = a[..na.max(1)].to_vec();
+        trim(&mut r);
+        return (vec![0], r);
+    }
+    if nb == 1 {
+        let (q, r) = divrem_mag_limb(&a[..na], b[0]);
+        return (q, vec![r]);
+    }
+    let s = b[nb - 1].leading_zeros() as usize;
+    let bn = shl_mag(&b[..nb], s);
+    let mut an = shl_mag(&a[..na], s);
+    an.resize(na + 1, 0);
+    let m = na - nb;
+    let mut q = vec![0u64; m + 1];
+    let base = 1u128 << 64;
+    for j in (0..=m).rev() {
+        let num = (an[j + nb] as u128) << 64 | an[j + nb - 1] as u128;
+        let mut qhat = num / bn[nb - 1] as u128;
+        let mut rhat = num % bn[nb - 1] as u128;
+        while qhat >= base || qhat * bn[nb - 2] as u128 > (rhat << 64 | an[j + nb - 2] as u128) {
+            qhat -= 1;
+            rhat += bn[nb - 1] as u128;
+            if rhat >= base {
+                break;
+            }
+        }
+        let mut carry = 0u128;
+        let mut borrow = 0u64;
+        for i in 0..nb {
+            let p = qhat * bn[i] as u128 + carry;
+            carry = p >> 64;
+            let (t, b1) = an[i + j].overflowing_sub(p as u64);
+            let (t, b2) = t.overflowing_sub(borrow);
+            an[i + j] = t;
+            borrow = b1 as u64 + b2 as u64;
+        }
+        let (t, b1) = an[j + nb].overflowing_sub(carry as u64);
+        let (t, b2) = t.overflowing_sub(borrow);
+        an[j + nb] = t;
+        if b1 || b2 {
+            qhat -= 1;
+            let mut carry = false;
+            for i in 0..nb {
+                let (t, c1) = an[i + j].overflowing_add(bn[i]);
+                let (t, c2) = t.overflowing_add(carry as u64);
+                an[i + j] = t;
+                carry = c1 || c2;
+            }
+            an[j + nb] = an[j + nb].wrapping_add(carry as u64);
+        }
+        q[j] = qhat as u64;
+    }
+    trim(&mut q);
+    (q, shr_mag(&an[..nb], s))
+}
+
+/* Return the two's complement of a signed magnitude in `len` limbs. */
+fn to_twos(neg: bool, v: &[u64], len: usize) -> Vec<u64> {
+    let mut ret = v.to_vec();
+    ret.resize(len, 0);
+    if neg {
+        let mut borrow = true;
+        for x in ret.iter_mut() {
+            let (t, b) = x.overflowing_sub(borrow as u64);
+            *x = !t;
+            borrow = b;
+        }
+    }
+    ret
+}
+
+/* Return the two's complements of `a` and `b` with one more limb than both, so that the sign bits are kept. */
+fn twos_pair(a: &BinaryInt, b: &BinaryInt) -> (Vec<u64>, Vec<u64>) {
+    let len = a.v.len().max(b.v.len()) + 1;
+    (to_twos(a.neg, &a.v, len), to_twos(b.neg, &b.v, len))
+}
+
+fn from_twos(mut v: Vec<u64>) -> BinaryInt {
+    let neg = v[v.len() - 1] >> 63 == 1;
+    if neg {
+        let mut carry = true;
+        for x in v.iter_mut() {
+            let (t, c) = (!*x).overflowing_add(carry as u64);
+            *x = t;
+            carry = c;
+        }
+    }
+    BinaryInt::from_parts(neg, v)
+}
+
+impl BinaryInt {
+
+    fn from_parts(neg: bool, mut v: Vec<u64>) -> Self {
+        trim(&mut v);
+        let mut ret = BinaryInt { neg, v };
+        ret.clean();
+        ret
+    }
+
+    fn clean(&mut self) {
+        trim(&mut self.v);
+        if self.neg && self.is_zero() {
+            self.neg = false;
+        }
+    }
+
+    /** Return `0`. */
+    pub fn zero() -> Self {
+        BinaryInt { neg: false, v: vec![0] }
+    }
+
+    /** Return `1`. */
+    pub fn one() -> Self {
+        BinaryInt { neg: false, v: vec![1] }
+    }
+
+    /** New a `BinaryInt` from a 64-bit signed integer. */
+    pub fn from_i64(v: i64) -> Self {
+        BinaryInt::from(v)
+    }
+
+    /** New a `BinaryInt` from a 128-bit signed integer. */
+    pub fn from_i128(v: i128) -> Self {
+        BinaryInt::from(v)
+    }
+
+    /** New a `BinaryInt` from a 128-bit unsigned integer. */
+    pub fn from_u128(v: u128) -> Self {
+        BinaryInt::from(v)
+    }
+
+    /** Return the value as `i128` if it fits. */
+    pub fn to_i128(&self) -> Option<i128> {
+        let m = self.to_u128_magnitude()?;
+        if self.neg {
+            if m <= 1u128 << 127 {
+                Some((m as i128).wrapping_neg())
+            } else {
+                None
+            }
+        } else {
+            i128::try_from(m).ok()
+        }
+    }
+
+    /** Return the value as `u128` if it fits. */
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.neg {
+            return None;
+        }
+        self.to_u128_magnitude()
+    }
+
+    fn to_u128_magnitude(&self) -> Option<u128> {
+        match self.v.len() {
+            1 => Some(self.v[0] as u128),
+            2 => Some((self.v[1] as u128) << 64 | self.v[0] as u128),
+            _ => None,
+        }
+    }
+
+    /** Return whether the value is zero. */
+    pub fn is_zero(&self) -> bool {
+        self.v.len() == 1 && self.v[0] == 0
+    }
+
+    /** Return whether the value is negative. */
+    pub fn is_negative(&self) -> bool {
+        self.neg
+    }
+
+    /** Return whether the value is positive. */
+    pub fn is_positive(&self) -> bool {
+        !self.neg && !self.is_zero()
+    }
+
+    /** Return `-1`, `0` or `1` according to the sign. */
+    pub fn signum(&self) -> i32 {
+        if self.neg {
+            -1
+        } else if self.is_zero() {
+            0
+        } else {
+            1
+        }
+    }
+
+    /** Return the absolute value. */
+    pub fn abs(&self) -> Self {
+        BinaryInt { neg: false, v: self.v.clone() }
+    }
+
+    /** Return the number of bits of the absolute value (`0` for zero). */
+    pub fn bits(&self) -> usize {
+        if self.is_zero() {
+            return 0;
+        }
+        self.v.len() * 64 - self.v[self.v.len() - 1].leading_zeros() as usize
+    }
+
+    /** Return the `i`th bit of the absolute value. */
+    pub fn bit(&self, i: usize) -> bool {
+        i / 64 < self.v.len() && (self.v[i / 64] >> (i % 64)) & 1 == 1
+    }
+
+    /** Return the little-endian 64-bit limbs of the absolute value. */
+    pub fn limbs(&self) -> &[u64] {
+        &self.v
+    }
+
+    /** Return (`self` / `other`, `self` % `other`) where the quotient truncates toward zero and the remainder has the sign of `self`, like the primitive integers. */
+    pub fn divrem(&self, other: &BinaryInt) -> (BinaryInt, BinaryInt) {
+        assert!(!other.is_zero(), "Division by zero.");
+        let (q, r) = divrem_mag(&self.v, &other.v);
+        (BinaryInt::from_parts(self.neg != other.neg, q), BinaryInt::from_parts(self.neg, r))
+    }
+
+    /** Return (`self` div `other`, `self` mod `other`) where the remainder is always non-negative. */
+    pub fn divrem_euclid(&self, other: &BinaryInt) -> (BinaryInt, BinaryInt) {
+        let (mut q, mut r) = self.divrem(other);
+        if r.neg {
+            if other.neg {
+                q += BinaryInt::one();
+                r -= other;
+            } else {
+                q -= BinaryInt::one();
+                r += other;
+            }
+        }
+        (q, r)
+    }
+
+    /** Return $self^e$ by repeated squaring. */
+    pub fn pow(&self, mut e: u32) -> BinaryInt {
+        let mut ans = BinaryInt::one();
+        let mut a = self.clone();
+        while e > 0 {
+            if e & 1 == 1 {
+                ans = &ans * &a;
+            }
+            e >>= 1;
+            if e > 0 {
+                a = &a * &a;
+            }
+        }
+        ans
+    }
+
+    /** Parse a string of digits in base `radix` ($2 \leq radix \leq 36$) with an optional sign. */
+    #[allow(clippy::result_unit_err)]
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<BinaryInt, ()> {
+        assert!((2..=36).contains(&radix), "The radix must be in [2, 36].");
+        let (neg, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let digits = digits.as_bytes();
+        if digits.is_empty() {
+            return Err(());
+        }
+        let mut values = Vec::with_capacity(digits.len());
+        for &c in digits {
+            match (c as char).to_digit(radix) {
+                Some(d) => values.push(d as u64),
+                None => return Err(()),
+            }
+        }
+        Ok(BinaryInt::from_parts(neg, digits_to_mag(&values, radix as u64)))
+    }
+
+    /** Return the digits in base `radix` ($2 \leq radix \leq 36$) in lowercase with a `-` for negative values. */
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!((2..=36).contains(&radix), "The radix must be in [2, 36].");
+        let digits = mag_to_digits(&self.v, radix as u64);
+        let mut s = String::with_capacity(digits.len() + 1);
+        if self.neg {
+            s.push('-');
+        }
+        s.extend(digits.iter().map(|&d| std::char::from_digit(d as u32, radix).unwrap()));
+        s
+    }
+}
+
+/* Return (`radix`^k, k) for the largest k such that `radix`^k fits in 64 bits. */
+fn chunk_power(radix: u64) -> (u64, usize) {
+    let mut p = radix;
+    let mut k = 1;
+    while let Some(np) = p.checked_mul(radix) {
+        p = np;
+        k += 1;
+    }
+    (p, k)
+}
+
+/* Convert the most-significant-first `digits` in base `radix` into a magnitude. Long inputs are split in halves aligned to powers $p^{2^i}$ of the chunk power $p$ and combined by one multiplication, so the time is $O(M(n) \log n)$. */
+fn digits_to_mag(digits: &[u64], radix: u64) -> Vec<u64> {
+    let (p, k) = chunk_power(radix);
+    if digits.len() <= k * DC_CONVERSION_THRESHOLD {
+        return digits_to_mag_basecase(digits, radix);
+    }
+    let mut pows = vec![vec![p]];
+    while k << pows.len() < digits.len() {
+        let last = &pows[pows.len() - 1];
+        pows.push(mul_mag(last, last));
+    }
+    digits_to_mag_rec(digits, radix, k, &pows)
+}
+
+fn digits_to_mag_rec(digits: &[u64], radix: u64, k: usize, pows: &[Vec<u64>]) -> Vec<u64> {
+    if digits.len() <= k * DC_CONVERSION_THRESHOLD {
+        return digits_to_mag_basecase(digits, radix);
+    }
+    let mut i = 0;
+    while k << (i + 1) < digits.len() {
+        i += 1;
+    }
+    let (high, low) = digits.split_at(digits.len() - (k << i));
+    let high = digits_to_mag_rec(high, radix, k, pows);
+    let low = digits_to_mag_rec(low, radix, k, pows);
+    add_mag(&mul_mag(&high, &pows[i]), &low)
+}
+
+/* Convert digits into a magnitude in $O(n^2)$ time, taking the digits a 64-bit chunk at a time. */
+fn digits_to_mag_basecase(digits: &[u64], radix: u64) -> Vec<u64> {
+    let (_, k) = chunk_power(radix);
+    let mut ret = vec![0u64];
+    let head = digits.len() % k;
+    let mut start = 0;
+    let mut len = if head == 0 { k } else { head };
+    while start < digits.len() {
+        let mut chunk = 0u64;
+        let mut mul = 1u64;
+        for &d in &digits[start..start + len] {
+            chunk = chunk * radix + d;
+            mul *= radix;
+        }
+        let mut carry = chunk as u128;
+        for x in ret.iter_mut() {
+            let t = *x as u128 * mul as u128 + carry;
+            *x = t as u64;
+            carry = t >> 64;
+        }
+        if carry > 0 {
+            ret.push(carry as u64);
+        }
+        start += len;
+        len = k;
+    }
+    trim(&mut ret);
+    ret
+}
+
+/* Convert a magnitude into most-significant-first digits in base `radix`. Large magnitudes are divided by $p^{2^i}$ recursively, so the time is $O(M(n) \log n)$ with the Newton division. The reciprocal of each $p^{2^i}$ is computed only once and shared by all the divisions by it. */
+fn mag_to_digits(v: &[u64], radix: u64) -> Vec<u8> {
+    let (p, k) = chunk_power(radix);
+    if significant_len(v) <= DC_CONVERSION_THRESHOLD {
+        return mag_to_digits_basecase(v, radix);
+    }
+    let mut pows = vec![vec![p]];
+    loop {
+        let last = &pows[pows.len() - 1];
+        let next = mul_mag(last, last);
+        if cmp_mag(&next, v) == Ordering::Greater {
+            break;
+        }
+        pows.push(next);
+    }
+    // the dividends are smaller than pows[i]^2, so the quotients have at most bits_mag(pows[i]) bits
+    let recips: Vec<Option<(Vec<u64>, usize)>> = pows.iter().map(|pow| {
+        if significant_len(pow) >= NEWTON_THRESHOLD {
+            let k = bits_mag(pow) + 1 + 32;
+            Some((recip_mag(pow, k), k))
+        } else {
+            None
+        }
+    }).collect();
+    let mut ret = Vec::with_capacity(k << pows.len());
+    mag_to_digits_rec(v, pows.len() - 1, radix, k, &pows, &recips, &mut ret);
+    let zeros = ret.iter().take_while(|&&d| d == 0).count();
+    ret.drain(..zeros.min(ret.len() - 1));
+    ret
+}
+
+/* Append exactly $k 2^{i+1}$ digits of `v` < `pows[i]`$^2$ to `out`, dividing by the precomputed `recips[i]` if any. */
+fn mag_to_digits_rec(v: &[u64], i: usize, radix: u64, k: usize, pows: &[Vec<u64>], recips: &[Option<(Vec<u64>, usize)>], out: &mut Vec<u8>) {
+    let width = k << (i + 1);
+    if significant_len(v) <= DC_CONVERSION_THRESHOLD {
+        let digits = mag_to_digits_basecase(v, radix);
+        out.resize(out.len() + width - digits.len(), 0);
+        out.extend_from_slice(&digits);
+        return;
+    }
+    let (q, r) = match &recips[i] {
+        Some((recip, rk)) => divrem_mag_recip(&v[..significant_len(v)], &pows[i], recip, *rk),
+        None => divrem_mag(v, &pows[i]),
+    };
+    mag_to_digits_rec(&q, i - 1, radix, k, pows, recips, out);
+    mag_to_digits_rec(&r, i - 1, radix, k, pows, recips, out);
+}
+
+/* Convert a magnitude into digits in $O(n^2)$ time by repeated division by a 64-bit chunk power. */
+fn mag_to_digits_basecase(v: &[u64], radix: u64) -> Vec<u8> {
+    let (p, k) = chunk_power(radix);
+    let mut chunks = vec![];
+    let mut cur = v.to_vec();
+    trim(&mut cur);
+    while !(cur.len() == 1 && cur[0] == 0) {
+        let (q, r) = divrem_mag_limb(&cur, p);
+        chunks.push(r);
+        cur = q;
+    }
+    if chunks.is_empty() {
+        return vec![0];
+    }
+    let mut ret = vec![];
+    for (i, &c) in chunks.iter().rev().enumerate() {
+        let mut d = vec![];
+        let mut c = c;
+        while c > 0 {
+            d.push((c % radix) as u8);
+            c /= radix;
+        }
+        if i > 0 {
+            d.resize(k, 0);
+        }
+        ret.extend(d.iter().rev());
+    }
+    ret
+}
+
+impl From<u8> for BinaryInt {
+    fn from(v: u8) -> Self {
+        BinaryInt::from(v as u128)
+    }
+}
+
+impl From<u16> for BinaryInt {
+    fn from(v: u16) -> Self {
+        BinaryInt::from(v as u128)
+    }
+}
+
+impl From<u32> for BinaryInt {
+    fn from(v: u32) -> Self {
+        BinaryInt::from(v as u128)
+    }
+}
+
+impl From<u64> for BinaryInt {
+    fn from(v: u64) -> Self {
+        BinaryInt::from(v as u128)
+    }
+}
+
+impl From<usize> for BinaryInt {
+    fn from(v: usize) -> Self {
+        BinaryInt::from(v as u128)
+    }
+}
+
+impl From<u128> for BinaryInt {
+    fn from(v: u128) -> Self {
+        BinaryInt::from_parts(false, vec![v as u64, (v >> 64) as u64])
+    }
+}
+
+impl From<i8> for BinaryInt {
+    fn from(v: i8) -> Self {
+        BinaryInt::from(v as i128)
+    }
+}
+
+impl From<i16> for BinaryInt {
+    fn from(v: i16) -> Self {
+        BinaryInt::from(v as i128)
+    }
+}
+
+impl From<i32> for BinaryInt {
+    fn from(v: i32) -> Self {
+        BinaryInt::from(v as i128)
+    }
+}
+
+impl From<i64> for BinaryInt {
+    fn from(v: i64) -> Self {
+        BinaryInt::from(v as i128)
+    }
+}
+
+impl From<isize> for BinaryInt {
+    fn from(v: isize) -> Self {
+        BinaryInt::from(v as i128)
+    }
+}
+
+impl From<i128> for BinaryInt {
+    fn from(v: i128) -> Self {
+        let m = v.unsigned_abs();
+        BinaryInt::from_parts(v < 0, vec![m as u64, (m >> 64) as u64])
+    }
+}
+
+macro_rules! impl_try_from_binary_int {
+    ($t:ty, $via:ident) => {
+        impl TryFrom<&BinaryInt> for $t {
+            type Error = ();
+
+            fn try_from(v: &BinaryInt) -> Result<$t, ()> {
+                v.$via().and_then(|x| <$t>::try_from(x).ok()).ok_or(())
+            }
+        }
+
+        impl TryFrom<BinaryInt> for $t {
+            type Error = ();
+
+            fn try_from(v: BinaryInt) -> Result<$t, ()> {
+                <$t>::try_from(&v)
+            }
+        }
+    };
+}
+
+impl_try_from_binary_int!(u8, to_u128);
+impl_try_from_binary_int!(u16, to_u128);
+impl_try_from_binary_int!(u32, to_u128);
+impl_try_from_binary_int!(u64, to_u128);
+impl_try_from_binary_int!(usize, to_u128);
+impl_try_from_binary_int!(u128, to_u128);
+impl_try_from_binary_int!(i8, to_i128);
+impl_try_from_binary_int!(i16, to_i128);
+impl_try_from_binary_int!(i32, to_i128);
+impl_try_from_binary_int!(i64, to_i128);
+impl_try_from_binary_int!(isize, to_i128);
+impl_try_from_binary_int!(i128, to_i128);
+
+impl Default for BinaryInt {
+    fn default() -> Self {
+        BinaryInt::zero()
+    }
+}
+
+impl PartialOrd for BinaryInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BinaryInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.neg, other.neg) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.v, &other.v),
+            (true, true) => cmp_mag(&other.v, &self.v),
+        }
+    }
+}
+
+impl Neg for BinaryInt {
+    type Output = BinaryInt;
+
+    fn neg(mut self) -> BinaryInt {
+        self.neg = !self.neg;
+        self.clean();
+        self
+    }
+}
+
+impl Neg for &BinaryInt {
+    type Output = BinaryInt;
+
+    fn neg(self) -> BinaryInt {
+        -self.clone()
+    }
+}
+
+/** `!x` is `-x - 1`. */
+impl Not for BinaryInt {
+    type Output = BinaryInt;
+
+    fn not(self) -> BinaryInt {
+        -self - BinaryInt::one()
+    }
+}
+
+impl Not for &BinaryInt {
+    type Output = BinaryInt;
+
+    fn not(self) -> BinaryInt {
+        !self.clone()
+    }
+}
+
+impl Add<&BinaryInt> for &BinaryInt {
+    type Output = BinaryInt;
+
+    fn add(self, other: &BinaryInt) -> BinaryInt {
+        if self.neg == other.neg {
+            return BinaryInt::from_parts(self.neg, add_mag(&self.v, &other.v));
+        }
+        match cmp_mag(&self.v, &other.v) {
+            Ordering::Less => BinaryInt::from_parts(other.neg, sub_mag(&other.v, &self.v)),
+            _ => BinaryInt::from_parts(self.neg, sub_mag(&self.v, &other.v)),
+        }
+    }
+}
+
+impl Sub<&BinaryInt> for &BinaryInt {
+    type Output = BinaryInt;
+
+    fn sub(self, other: &BinaryInt) -> BinaryInt {
+        if self.neg != other.neg {
+            return BinaryInt::from_parts(self.neg, add_mag(&self.v, &other.v));
+        }
+        match cmp_mag(&self.v, &other.v) {
+            Ordering::Less => BinaryInt::from_parts(!self.neg, sub_mag(&other.v, &self.v)),
+            _ => BinaryInt::from_parts(self.neg, sub_mag(&self.v, &other.v)),
+        }
+    }
+}
+
+impl Mul<&BinaryInt> for &BinaryInt {
+    type Output = BinaryInt;
+
+    fn mul(self, other: &BinaryInt) -> BinaryInt {
+        BinaryInt::from_parts(self.neg != other.neg, mul_mag(&self.v, &other.v))
+    }
+}
+
+impl Div<&BinaryInt> for &BinaryInt {
+    type Output = BinaryInt;
+
+    fn div(self, other: &BinaryInt) -> BinaryInt {
+        self.divrem(other).0
+    }
+}
+
+impl Rem<&BinaryInt> for &BinaryInt {
+    type Output = BinaryInt;
+
+    fn rem(self, other: &BinaryInt) -> BinaryInt {
+        self.divrem(other).1
+    }
+}
+
+impl BitAnd<&BinaryInt> for &BinaryInt {
+    type Output = BinaryInt;
+
+    fn bitand(self, other: &BinaryInt) -> BinaryInt {
+        let (a, b) = twos_pair(self, other);
+        from_twos(a.iter().zip(b.iter()).map(|(x, y)| x & y).collect())
+    }
+}
+
+impl BitOr<&BinaryInt> for &BinaryInt {
+    type Output = BinaryInt;
+
+    fn bitor(self, other: &BinaryInt) -> BinaryInt {
+        let (a, b) = twos_pair(self, other);
+        from_twos(a.iter().zip(b.iter()).map(|(x, y)| x | y).collect())
+    }
+}
+
+impl BitXor<&BinaryInt> for &BinaryInt {
+    type Output = BinaryInt;
+
+    fn bitxor(self, other: &BinaryInt) -> BinaryInt {
+        let (a, b) = twos_pair(self, other);
+        from_twos(a.iter().zip(b.iter()).map(|(x, y)| x ^ y).collect())
+    }
+}
+
+impl Shl<usize> for &BinaryInt {
+    type Output = BinaryInt;
+
+    fn shl(self, k: usize) -> BinaryInt {
+        BinaryInt::from_parts(self.neg, shl_mag(&self.v, k))
+    }
+}
+
+/** `x >> k` is $\lfloor x / 2^k \rfloor$. */
+impl Shr<usize> for &BinaryInt {
+    type Output = BinaryInt;
+
+    fn shr(self, k: usize) -> BinaryInt {
+        if !self.neg {
+            return BinaryInt::from_parts(false, shr_mag(&self.v, k));
+        }
+        let m = sub_mag(&self.v, &[1]);
+        BinaryInt::from_parts(true, add_mag(&shr_mag(&m, k), &[1]))
+    }
+}
+
+impl Shl<usize> for BinaryInt {
+    type Output = BinaryInt;
+
+    fn shl(self, k: usize) -> BinaryInt {
+        &self << k
+    }
+}
+
+impl Shr<usize> for BinaryInt {
+    type Output = BinaryInt;
+
+    fn shr(self, k: usize) -> BinaryInt {
+        &self >> k
+    }
+}
+
+impl ShlAssign<usize> for BinaryInt {
+    fn shl_assign(&mut self, k: usize) {
+        *self = &*self << k;
+    }
+}
+
+impl ShrAssign<usize> for BinaryInt {
+    fn shr_assign(&mut self, k: usize) {
+        *self = &*self >> k;
+    }
+}
+
+/* Forward the owned variants of a binary operator and its assignment to the implementation on references. */
+macro_rules! forward_binop {
+    ($op:ident, $f:ident, $op_assign:ident, $f_assign:ident) => {
+        impl $op<BinaryInt> for BinaryInt {
+            type Output = BinaryInt;
+
+            fn $f(self, other: BinaryInt) -> BinaryInt {
+                (&self).$f(&other)
+            }
+        }
+
+        impl $op<&BinaryInt> for BinaryInt {
+            type Output = BinaryInt;
+
+            fn $f(self, other: &BinaryInt) -> BinaryInt {
+                (&self).$f(other)
+            }
+        }
+
+        impl $op<BinaryInt> for &BinaryInt {
+            type Output = BinaryInt;
+
+            fn $f(self, other: BinaryInt) -> BinaryInt {
+                self.$f(&other)
+            }
+        }
+
+        impl $op_assign<BinaryInt> for BinaryInt {
+            fn $f_assign(&mut self, other: BinaryInt) {
+                *self = (&*self).$f(&other);
+            }
+        }
+
+        impl $op_assign<&BinaryInt> for BinaryInt {
+            fn $f_assign(&mut self, other: &BinaryInt) {
+                *self = (&*self).$f(other);
+            }
+        }
+    };
+}
+
+forward_binop!(Add, add, AddAssign, add_assign);
+forward_binop!(Sub, sub, SubAssign, sub_assign);
+forward_binop!(Mul, mul, MulAssign, mul_assign);
+forward_binop!(Div, div, DivAssign, div_assign);
+forward_binop!(Rem, rem, RemAssign, rem_assign);
+forward_binop!(BitAnd, bitand, BitAndAssign, bitand_assign);
+forward_binop!(BitOr, bitor, BitOrAssign, bitor_assign);
+forward_binop!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+impl Sum for BinaryInt {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(BinaryInt::zero(), |a, b| a + b)
+    }
+}
+
+impl<'a> Sum<&'a BinaryInt> for BinaryInt {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(BinaryInt::zero(), |a, b| a + b)
+    }
+}
+
+impl Product for BinaryInt {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(BinaryInt::one(), |a, b| a * b)
+    }
+}
+
+impl<'a> Product<&'a BinaryInt> for BinaryInt {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(BinaryInt::one(), |a, b| a * b)
+    }
+}
+
+/** Parse a decimal integer, or a hexadecimal integer with a `0x` prefix, with an optional sign. */
+impl FromStr for BinaryInt {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        let s = s.trim();
+        let (sign, body) = match s.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", s.strip_prefix('+').unwrap_or(s)),
+        };
+        if let Some(hex) = body.strip_prefix("0x").or_else(|| body.strip_prefix("0X")) {
+            if hex.starts_with(['+', '-']) {
+                return Err(());
+            }
+            return BinaryInt::from_str_radix(&format!("{}{}", sign, hex), 16);
+        }
+        if body.starts_with(['+', '-']) {
+            return Err(());
+        }
+        BinaryInt::from_str_radix(&format!("{}{}", sign, body), 10)
+    }
+}
+
+impl fmt::Display for BinaryInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = mag_to_digits(&self.v, 10);
+        let s: String = digits.iter().map(|&d| (b'0' + d) as char).collect();
+        f.pad_integral(!self.neg, "", &s)
+    }
+}
+
+impl fmt::LowerHex for BinaryInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.neg, "0x", &self.abs().to_str_radix(16))
+    }
+}
+
+impl fmt::UpperHex for BinaryInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.neg, "0x", &self.abs().to_str_radix(16).to_uppercase())
+    }
+}
@@ -0,0 +1,129 @@
+use crate::math::modint::ModInt;
+
+/** Return the smallest primitive root of the prime `M`, factorizing $M-1$ by trial division. */
+fn primitive_root<const M: u64>() -> ModInt<M> {
+    let mut factors = vec![];
+    let mut n = M - 1;
+    let mut i = 2;
+    while i * i <= n {
+        if n.is_multiple_of(i) {
+            factors.push(i);
+            while n.is_multiple_of(i) {
+                n /= i;
+            }
+        }
+        i += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    let mut g = 2;
+    loop {
+        let x = ModInt::<M>::new(g);
+        if factors.iter().all(|&p| x.pow((M - 1) / p) != ModInt::one()) {
+            return x;
+        }
+        g += 1;
+    }
+}
+
+/** `ntt` is the number theoretic transform over $\mathbf{Z}_M$ in $O(n \log n)$ time, which is the inverse transform (including the division by $n$) if `invert=true`. `M` must be a prime and the length $n$ of `a` must be a power of two dividing $M-1$. */
+pub fn ntt<const M: u64>(a: &mut [ModInt<M>], invert: bool) {
+    let n = a.len();
+    assert!(n.is_power_of_two(), "The length must be a power of two.");
+    assert!((M - 1).is_multiple_of(n as u64), "The length is too large for the modulus {}.", M);
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j ^= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+    let g = primitive_root::<M>();
+    let mut ws = Vec::with_capacity(n / 2);
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        let w = if invert { g.pow((M - 1) / len as u64).inv() } else { g.pow((M - 1) / len as u64) };
+        ws.clear();
+        let mut cur = ModInt::one();
+        for _ in 0..half {
+            ws.push(cur);
+            cur *= w;
+        }
+        for block in a.chunks_mut(len) {
+            let (lo, hi) = block.split_at_mut(half);
+            for k in 0..half {
+                let u = lo[k];
+                let v = hi[k] * ws[k];
+                lo[k] = u + v;
+                hi[k] = u - v;
+            }
+        }
+        len <<= 1;
+    }
+    if invert {
+        let inv_n = ModInt::<M>::new(n as u64).inv();
+        for x in a.iter_mut() {
+            *x *= inv_n;
+        }
+    }
+}
+
+/** `convolution` computes $c_k = \sum_{i+j=k} a_i b_j$ over $\mathbf{Z}_M$ by `ntt` in $O((n+m) \log (n+m))$ time. `M` must be a prime such that a power of two no less than $n+m-1$ divides $M-1$ (e.g. $998244353$). */
+pub fn convolution<const M: u64>(a: &[ModInt<M>], b: &[ModInt<M>]) -> Vec<ModInt<M>> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let len = a.len() + b.len() - 1;
+    if a.len().min(b.len()) <= 32 {
+        let mut c = vec![ModInt::zero(); len];
+        for i in 0..a.len() {
+            for j in 0..b.len() {
+                c[i + j] += a[i] * b[j];
+            }
+        }
+        return c;
+    }
+    let n = len.next_power_of_two();
+    let mut fa = a.to_vec();
+    fa.resize(n, ModInt::zero());
+    let mut fb = b.to_vec();
+    fb.resize(n, ModInt::zero());
+    ntt(&mut fa, false);
+    ntt(&mut fb, false);
+    for i in 0..n {
+        fa[i] *= fb[i];
+    }
+    ntt(&mut fa, true);
+    fa.truncate(len);
+    fa
+}
+
+const M1: u64 = 167772161; // 5 * 2^25 + 1
+const M2: u64 = 469762049; // 7 * 2^26 + 1
+const M3: u64 = 998244353; // 119 * 2^23 + 1
+
+/** `convolution_u64` computes the exact convolution $c_k = \sum_{i+j=k} a_i b_j$ of integer sequences by three `ntt`s modulo $167772161$, $469762049$ and $998244353$ combined by the Chinese remainder theorem. Every $c_k$ must be smaller than their product (about $7.9 \times 10^{25}$), and $n+m-1$ must be at most $2^{23}$. */
+pub fn convolution_u64(a: &[u64], b: &[u64]) -> Vec<u128> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let c1 = convolution(&a.iter().map(|&x| ModInt::<M1>::new(x)).collect::<Vec<_>>(), &b.iter().map(|&x| ModInt::<M1>::new(x)).collect::<Vec<_>>());
+    let c2 = convolution(&a.iter().map(|&x| ModInt::<M2>::new(x)).collect::<Vec<_>>(), &b.iter().map(|&x| ModInt::<M2>::new(x)).collect::<Vec<_>>());
+    let c3 = convolution(&a.iter().map(|&x| ModInt::<M3>::new(x)).collect::<Vec<_>>(), &b.iter().map(|&x| ModInt::<M3>::new(x)).collect::<Vec<_>>());
+    let m1_inv_m2 = ModInt::<M2>::new(M1).inv();
+    let m1_inv_m3 = ModInt::<M3>::new(M1).inv();
+    let m2_inv_m3 = ModInt::<M3>::new(M2).inv();
+    (0..c1.len()).map(|i| {
+        let x1 = c1[i].value();
+        let x2 = ((c2[i] - ModInt::new(x1)) * m1_inv_m2).value();
+        let x3 = (((c3[i] - ModInt::new(x1)) * m1_inv_m3 - ModInt::new(x2)) * m2_inv_m3).value();
+        x1 as u128 + x2 as u128 * M1 as u128 + x3 as u128 * (M1 as u128 * M2 as u128)
+    }).collect()
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use crate::math::{basic::{gcd_u64, mul_mod_u64, pow_mod_u64, inv_mod_u64, crt_u64}, prime::ExtractPrimeFactors};
+
+/** `DiscreteLog` solves the problems on the multiplicative group modulo $m$: primitive roots, multiplicative orders and discrete logarithms. The group orders are factorized by the given `ExtractPrimeFactors` implementor (e.g. `PollardRho`). */
+#[derive(Clone, Debug)]
+pub struct DiscreteLog<F: ExtractPrimeFactors> {
+    factorizer: F,
+}
+
+impl<F: ExtractPrimeFactors> DiscreteLog<F> {
+    /** New a `DiscreteLog` with a given prime factor extractor `factorizer`. */
+    pub fn new(factorizer: &F) -> DiscreteLog<F> {
+        DiscreteLog {
+            factorizer: factorizer.clone(),
+        }
+    }
+
+    /** Return the smallest primitive root modulo `m`, or `None` if there is no primitive root (i.e. `m` is not $1$, $2$, $4$, $p^k$ or $2p^k$ for an odd prime $p$). It takes the time of factorizing $m$ and $\varphi(m)$ plus $O(g \log^2 m)$ where $g$ is the answer. */
+    pub fn primitive_root(&mut self, m: &u64) -> Option<u64> {
+        let m = *m;
+        if m <= 4 {
+            return match m {
+                0 => None,
+                1 => Some(0),
+                _ => Some(m - 1),
+            };
+        }
+        let factorization = self.factorizer.factorize_u64(&m);
+        let factors = factorization.factors();
+        let odd = factors.iter().filter(|&&(p, _)| p != 2).count();
+        let twos = factors.iter().find(|&&(p, _)| p == 2).map_or(0, |&(_, e)| e);
+        if odd != 1 || twos > 1 {
+            return None;
+        }
+        let phi = factorization.phi();
+        let qs = self.factorizer.factorize_u64(&phi);
+        (2..m).find(|&g| gcd_u64(g, m) == 1 && qs.primes().all(|q| pow_mod_u64(&g, &(phi / q), &m) != 1))
+    }
+
+    /** Return the multiplicative order of `a` modulo `m`, i.e. the smallest $k > 0$ such that $a^k \equiv 1 \pmod m$, or `None` if `a` and `m` are not coprime. It takes the time of factorizing $m$ and $\varphi(m)$ plus $O(\log^3 m)$. */
+    pub fn multiplicative_order(&mut self, a: &u64, m: &u64) -> Option<u64> {
+        let (a, m) = (*a, *m);
+        if m == 0 || gcd_u64(a % m, m) != 1 {
+            return None;
+        }
+        if m == 1 {
+            return Some(1);
+        }
+        let mut order = self.factorizer.factorize_u64(&m).phi();
+        for q in self.factorizer.factorize_u64(&order).primes() {
+            while order.is_multiple_of(q) && pow_mod_u64(&a, &(order / q), &m) == 1 {
+                order /= q;
+            }
+        }
+        Some(order)
+    }
+
+    /** Return the smallest $x \geq 0$ such that $a^x \equiv b \pmod m$, or `None` if there is no such $x$. It removes the common factors of $a$ and $m$ first, and then uses `discrete_log_pohlig_hellman` if the order of $a$ is not a prime and the baby-step giant-step algorithm otherwise. `m` must be positive. */
+    pub fn discrete_log(&mut self, a: &u64, b: &u64, m: &u64) -> Option<u64> {
+        let (k, a, b, m) = match remove_common_factors(*a, *b, *m) {
+            Ok(x) => return Some(x),
+            Err(None) => return None,
+            Err(Some(x)) => x,
+        };
+        let order = self.multiplicative_order(&a, &m).unwrap();
+        let y = if order == 1 || self.factorizer.factorize_u64(&order).is_prime() {
+            bsgs(a, b, m, order)
+        } else {
+            self.discrete_log_pohlig_hellman(&a, &b, &m)
+        };
+        y.map(|y| y + k)
+    }
+
+    /** Return the smallest $x \geq 0$ such that $a^x \equiv b \pmod m$ for coprime `a` and `m` by the Pohlig-Hellman algorithm, or `None` if there is no such $x$. It takes $O(\sum_i e_i (\sqrt{q_i} + \log m))$ modular operations besides factorizing, where $\prod_i q_i^{e_i}$ is the order of $a$, so it is fast when the order is smooth. `m` must be positive. */
+    pub fn discrete_log_pohlig_hellman(&mut self, a: &u64, b: &u64, m: &u64) -> Option<u64> {
+        let (a, b, m) = (*a % *m, *b % *m, *m);
+        if m == 1 {
+            return Some(0);
+        }
+        let n = self.multiplicative_order(&a, &m).expect("`a` and `m` must be coprime.");
+        let mut congruences = Vec::new();
+        for &(q, e) in self.factorizer.factorize_u64(&n).factors() {
+            let gamma = pow_mod_u64(&a, &(n / q), &m);
+            let a_inv = inv_mod_u64(&a, &m).unwrap();
+            let mut x = 0u64;
+            let mut qk = 1u64;
+            for _ in 0..e {
+                let h = mul_mod_u64(&b, &pow_mod_u64(&a_inv, &x, &m), &m);
+                let h = pow_mod_u64(&h, &(n / qk / q), &m);
+                let d = bsgs(gamma, h, m, q)?;
+                x += d * qk;
+                qk *= q;
+            }
+            congruences.push((x, qk));
+        }
+        let (x, _) = crt_u64(&congruences).unwrap();
+        if pow_mod_u64(&a, &x, &m) == b {
+            Some(x)
+        } else {
+            None
+        }
+    }
+
+    /** Return the smallest $x \geq 0$ such that $a^x \equiv b \pmod m$ by the (extended) baby-step giant-step algorithm, or `None` if there is no such $x$. `a` and `m` need not be coprime. It takes $O(\sqrt{m})$ time and space. `m` must be positive. */
+    pub fn discrete_log_bsgs(&mut self, a: &u64, b: &u64, m: &u64) -> Option<u64> {
+        match remove_common_factors(*a, *b, *m) {
+            Ok(x) => Some(x),
+            Err(None) => None,
+            Err(Some((k, a, b, m))) => bsgs(a, b, m, m).map(|y| y + k),
+        }
+    }
+}
+
+/** Reduce $a^x \equiv b \pmod m$ to $a^{x-k} \equiv b' \pmod {m'}$ with $\gcd(a, m') = 1$ by dividing out $\gcd(a, m)$ repeatedly. It returns `Ok(x)` if a solution $x < k$ is found, `Err(None)` if there is no solution and `Err(Some((k, a', b', m')))` otherwise. */
+fn remove_common_factors(a: u64, b: u64, m: u64) -> Result<u64, Option<(u64, u64, u64, u64)>> {
+    assert!(m > 0, "The modulus must be positive.");
+    let (mut a, mut b, mut m) = (a % m, b % m, m);
+    if b == 1 % m {
+        return Ok(0);
+    }
+    let mut k = 0;
+    let mut c = 1 % m;
+    loop {
+        let g = gcd_u64(a, m);
+        if g == 1 {
+            break;
+        }
+        if !b.is_multiple_of(g) {
+            return Err(None);
+        }
+        m /= g;
+        b /= g;
+        c = mul_mod_u64(&(c % m), &(a / g % m), &m);
+        k += 1;
+        if c == b {
+            return Ok(k);
+        }
+    }
+    a %= m;
+    let b = mul_mod_u64(&b, &inv_mod_u64(&c, &m).unwrap(), &m);
+    Err(Some((k, a, b, m)))
+}
+
+/** Return the smallest $x$ in $[0, n)$ such that $a^x \equiv b \pmod m$ for coprime `a` and `m` in $O(\sqrt{n})$ time and space. */
+fn bsgs(a: u64, b: u64, m: u64, n: u64) -> Option<u64> {
+    let s = (n as f64).sqrt().ceil() as u64 + 1;
+    let mut baby = HashMap::with_capacity(s as usize);
+    let mut cur = 1 % m;
+    for j in 0..s {
+        baby.entry(cur).or_insert(j);
+        cur = mul_mod_u64(&cur, &a, &m);
+    }
+    let giant = pow_mod_u64(&inv_mod_u64(&a, &m).unwrap(), &s, &m);
+    let mut cur = b;
+    for i in 0..s {
+        if let Some(&j) = baby.get(&cur) {
+            let x = i * s + j;
+            return if x < n { Some(x) } else { None };
+        }
+        cur = mul_mod_u64(&cur, &giant, &m);
+    }
+    None
+}
@@ -0,0 +1,209 @@
+use std::{fmt::{Debug, Display}, hash::Hash, ops::{Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, DivAssign, Mul, MulAssign, Not, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign}, str::FromStr};
+use crate::math::basic::add_mod;
+
+/** `Integer` introduces a trait for primitive integers, so that an algorithm can be written once instead of once per width. It is implemented for `u8`, `u16`, `u32`, `u64`, `u128`, `usize`, `i8`, `i16`, `i32`, `i64`, `i128` and `isize`. */
+pub trait Integer: Copy + Debug + Display + Default + Eq + Ord + Hash + FromStr + Send + Sync + 'static
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Rem<Output = Self>
+    + AddAssign + SubAssign + MulAssign + DivAssign + RemAssign
+    + BitAnd<Output = Self> + BitOr<Output = Self> + BitXor<Output = Self> + Not<Output = Self>
+    + BitAndAssign + BitOrAssign + BitXorAssign
+    + Shl<u32, Output = Self> + Shr<u32, Output = Self> + ShlAssign<u32> + ShrAssign<u32> {
+    /** The unsigned integer of the same width. */
+    type Unsigned: UnsignedInt;
+
+    /** `0` */
+    const ZERO: Self;
+    /** `1` */
+    const ONE: Self;
+    /** The smallest value. */
+    const MIN: Self;
+    /** The largest value. */
+    const MAX: Self;
+    /** The number of bits. */
+    const BITS: u32;
+    /** Whether the type is signed. */
+    const SIGNED: bool;
+
+    /** Map `self` to `Self::Unsigned` by increasing $2^{BITS-1}$ for signed types, so that the order is preserved (like `from_to::ixux`). */
+    fn to_ordered_unsigned(self) -> Self::Unsigned;
+
+    /** The inverse of `to_ordered_unsigned` (like `from_to::uxix`). */
+    fn from_ordered_unsigned(x: Self::Unsigned) -> Self;
+
+    /** Return the lowest `BITS` bits of `x` as `Self` (the same as `x as Self`). */
+    fn from_u128(x: u128) -> Self;
+
+    /** Return `self as u128`, i.e. sign-extended for negative values. */
+    fn as_u128(self) -> u128;
+
+    /** Checked addition. */
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /** Checked subtraction. */
+    fn checked_sub(self, other: Self) -> Option<Self>;
+
+    /** Checked multiplication. */
+    fn checked_mul(self, other: Self) -> Option<Self>;
+
+    /** Wrapping addition. */
+    fn wrapping_add(self, other: Self) -> Self;
+
+    /** Wrapping subtraction. */
+    fn wrapping_sub(self, other: Self) -> Self;
+
+    /** Wrapping multiplication. */
+    fn wrapping_mul(self, other: Self) -> Self;
+
+    /** Count the number of 1 bits. */
+    fn count_ones(self) -> u32;
+
+    /** Count the number of leading 0 bits. */
+    fn leading_zeros(self) -> u32;
+
+    /** Count the number of trailing 0 bits. */
+    fn trailing_zeros(self) -> u32;
+}
+
+/** `UnsignedInt` introduces a trait for primitive unsigned integers. */
+pub trait UnsignedInt: Integer<Unsigned = Self> {
+    /** Return (`self` * `other`) mod `m` without overflow. */
+    fn mul_mod(self, other: Self, m: Self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($t:ty, $u:ty, $signed:expr) => {
+        impl Integer for $t {
+            type Unsigned = $u;
+
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+            const BITS: u32 = <$t>::BITS;
+            const SIGNED: bool = $signed;
+
+            #[inline(always)]
+            fn to_ordered_unsigned(self) -> $u {
+                if $signed {
+                    (self as $u) ^ (1 << (<$u>::BITS - 1))
+                } else {
+                    self as $u
+                }
+            }
+
+            #[inline(always)]
+            fn from_ordered_unsigned(x: $u) -> Self {
+                if $signed {
+                    (x ^ (1 << (<$u>::BITS - 1))) as $t
+                } else {
+                    x as $t
+                }
+            }
+
+            #[inline(always)]
+            fn from_u128(x: u128) -> Self {
+                x as $t
+            }
+
+            #[inline(always)]
+            fn as_u128(self) -> u128 {
+                self as u128
+            }
+
+            #[inline(always)]
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            #[inline(always)]
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$t>::checked_sub(self, other)
+            }
+
+            #[inline(always)]
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+
+            #[inline(always)]
+            fn wrapping_add(self, other: Self) -> Self {
+                <$t>::wrapping_add(self, other)
+            }
+
+            #[inline(always)]
+            fn wrapping_sub(self, other: Self) -> Self {
+                <$t>::wrapping_sub(self, other)
+            }
+
+            #[inline(always)]
+            fn wrapping_mul(self, other: Self) -> Self {
+                <$t>::wrapping_mul(self, other)
+            }
+
+            #[inline(always)]
+            fn count_ones(self) -> u32 {
+                <$t>::count_ones(self)
+            }
+
+            #[inline(always)]
+            fn leading_zeros(self) -> u32 {
+                <$t>::leading_zeros(self)
+            }
+
+            #[inline(always)]
+            fn trailing_zeros(self) -> u32 {
+                <$t>::trailing_zeros(self)
+            }
+        }
+    };
+}
+
+macro_rules! impl_unsigned_int {
+    ($t:ty, $wide:ty) => {
+        impl UnsignedInt for $t {
+            #[inline(always)]
+            fn mul_mod(self, other: Self, m: Self) -> Self {
+                ((self as $wide) * (other as $wide) % (m as $wide)) as $t
+            }
+        }
+    };
+}
+
+impl_integer!(u8, u8, false);
+impl_integer!(u16, u16, false);
+impl_integer!(u32, u32, false);
+impl_integer!(u64, u64, false);
+impl_integer!(u128, u128, false);
+impl_integer!(usize, usize, false);
+impl_integer!(i8, u8, true);
+impl_integer!(i16, u16, true);
+impl_integer!(i32, u32, true);
+impl_integer!(i64, u64, true);
+impl_integer!(i128, u128, true);
+impl_integer!(isize, usize, true);
+
+impl_unsigned_int!(u8, u16);
+impl_unsigned_int!(u16, u32);
+impl_unsigned_int!(u32, u64);
+impl_unsigned_int!(u64, u128);
+impl_unsigned_int!(usize, u128);
+
+impl UnsignedInt for u128 {
+    /** Multiply by doubling and adding in $O(128)$ time, since there is no wider primitive type. */
+    fn mul_mod(self, other: Self, m: Self) -> Self {
+        let mut a = self % m;
+        let mut b = other % m;
+        if a.leading_zeros() + b.leading_zeros() >= 128 {
+            return a * b % m;
+        }
+        let mut ans = 0u128;
+        while b > 0 {
+            if b & 1 == 1 {
+                ans = add_mod(ans, a, m);
+            }
+            a = add_mod(a, a, m);
+            b >>= 1;
+        }
+        ans
+    }
+}
@@ -1,4 +1,4 @@
-use std::cmp;
+use std::{cmp, collections::HashMap};
 
 /** `ZeroOneLinearEquation` is a linear equation in $\mathbf{Z}^{n}_2$. */
 #[derive(Clone, Debug)]
@@ -113,12 +113,42 @@ impl PartialOrd for ZeroOneLinearEquation {
     }
 }
 
+/** `XorLinearEquationSolution` is the solution set $x_0 + \mathrm{span}(N)$ of a system $Ax = b$ over $\mathbf{Z}_2$, given by `XorLinearEquationSystem::solve`. */
+#[derive(Clone, Debug)]
+pub struct XorLinearEquationSolution {
+    solution: ZeroOneLinearEquation,
+    null_space: Vec<ZeroOneLinearEquation>,
+    rank: usize,
+}
+
+impl XorLinearEquationSolution {
+
+    /** Return a particular solution $x_0$, where all the free variables are `0`. */
+    pub fn solution(&self) -> &ZeroOneLinearEquation {
+        &self.solution
+    }
+
+    /** Return a basis $N$ of the null space of $A$, which has one vector for each free variable. */
+    pub fn null_space(&self) -> &[ZeroOneLinearEquation] {
+        &self.null_space
+    }
+
+    /** Return the rank of $A$. */
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+}
+
 /** `XorLinearEquationSystem` is a system of linear equations, ($\mathbf{Z}^{n}_2$, `Xor`). */
 #[derive(Clone, Debug)]
 pub struct XorLinearEquationSystem {
     equations: Vec<ZeroOneLinearEquation>,  // basis
     equation_size: usize,                   // the size of dimension
     highest_one_bit: Vec<usize>,                // the highest 1-bit of each equation in the basis
+    sources: Vec<ZeroOneLinearEquation>,    // the k-th bit of sources[i] means that the i-th equation in the basis contains independent[k]
+    independent: Vec<usize>,                // the original indices of the equations which extended the basis
+    equation_num: usize,                    // the number of original equations inserted into the basis
+    reduced: bool,                          // whether the equations have been replaced by the basis
 }
 
 impl XorLinearEquationSystem {
@@ -129,6 +159,10 @@ impl XorLinearEquationSystem {
             equations: vec![ZeroOneLinearEquation::new(equation_size); equation_num],
             equation_size: equation_size,
             highest_one_bit: vec![],
+            sources: vec![],
+            independent: vec![],
+            equation_num: 0,
+            reduced: false,
         }
     }
 
@@ -200,39 +234,17 @@ impl XorLinearEquationSystem {
         self.equations.len() == self.equation_size
     }
 
-    /** Compute the basis. Note that for each pair of equation[i] and equation[j], the equation[j].get(highest_one_bit[i])=0 and equation[i].get(highest_one_bit[j])=0. */
+    /** Compute the basis by inserting the equations one by one, where the `i`th equation becomes the original equation `i` (see `represent`). Note that for each pair of equation[i] and equation[j], the equation[j].get(highest_one_bit[i])=0 and equation[i].get(highest_one_bit[j])=0. */
     pub fn compute_basis(&mut self) {
-        let mut l = 0usize;
-        for i in (0..self.equation_size).rev() {
-            let mut j = l;
-            while j < self.equations.len() && !self.equations[j].get_i(i) {
-                j += 1;
-            }
-            if j == self.equations.len() {
-                continue;
-            }
-            self.highest_one_bit.push(i);
-
-            self.equations.swap(l, j);
-
-            let tmp = self.equations[l].clone();
-            
-            for k in 0..l {
-                if self.equations[k].get_i(i) {
-                    self.equations[k].xor(& tmp);
-                }
-            }
-
-            for k in l+1..self.equations.len() {
-                if self.equations[k].get_i(i) {
-                    self.equations[k].xor(& tmp);
-                }
-            }
-
-
-            l += 1;
+        let equations = std::mem::take(&mut self.equations);
+        self.highest_one_bit.clear();
+        self.sources.clear();
+        self.independent.clear();
+        self.equation_num = 0;
+        self.reduced = true;
+        for equation in equations {
+            self.insert_basis(equation);
         }
-        self.equations.truncate(l);
     }
 
     /** Check if the `equation` can be represented by the system. */
@@ -246,12 +258,17 @@ impl XorLinearEquationSystem {
         equation.highest_one_bit().is_none()
     }
 
-    /** Insert a new equation into the basis. If the basis is extended, return true, otherwise false. */
+    /** Insert a new equation into the basis. If the basis is extended, return true, otherwise false. The equation becomes the next original equation (see `represent`) in either case. */
     pub fn insert_basis(&mut self, mut equation: ZeroOneLinearEquation) -> bool {
         assert!(self.equation_size == equation.equation_size);
+        let index = self.equation_num;
+        self.equation_num += 1;
+        self.reduced = true;
+        let mut source = ZeroOneLinearEquation::new(self.equation_size);
         for i in 0..self.equations.len() {
             if equation.get_i(self.highest_one_bit[i]) {
                 equation.xor(&self.equations[i]);
+                source.xor(&self.sources[i]);
             }
             let highest_one_bit = equation.highest_one_bit();
             if highest_one_bit.is_none() {
@@ -262,15 +279,20 @@ impl XorLinearEquationSystem {
                 for j in i..self.equations.len() {
                     if equation.get_i(self.highest_one_bit[j]) {
                         equation.xor(&self.equations[j]);
+                        source.xor(&self.sources[j]);
                     }
                 }
+                source.set_i(self.independent.len());
+                self.independent.push(index);
                 for j in 0..i {
                     if self.equations[j].get_i(highest_one_bit) {
                         self.equations[j].xor(&equation);
+                        self.sources[j].xor(&source);
                     }
                 }
                 self.equations.insert(i, equation);
                 self.highest_one_bit.insert(i, highest_one_bit);
+                self.sources.insert(i, source);
                 return true;
             }
         }
@@ -279,25 +301,193 @@ impl XorLinearEquationSystem {
             return false;   
         } 
         let highest_one_bit = highest_one_bit.unwrap();
+        source.set_i(self.independent.len());
+        self.independent.push(index);
         for i in 0..self.equations.len() {
             if self.equations[i].get_i(highest_one_bit) {
                 self.equations[i].xor(&equation);
+                self.sources[i].xor(&source);
             }
         }
         self.equations.push(equation);
         self.highest_one_bit.push(highest_one_bit);
+        self.sources.push(source);
         true
     }
 
-    /** Get the maximum equation that can be represented by the system. */
+    /** Return the indices of the original equations whose xor is `equation` in increasing order, or `None` if it cannot be represented by the basis. The equations given at `compute_basis` are the original equations $0, 1, \dots$ and the ones passed to `insert_basis` afterwards are numbered on in the order of the calls. Only the equations which extended the basis can appear, so the answer is unique. */
+    pub fn represent(&self, mut equation: ZeroOneLinearEquation) -> Option<Vec<usize>> {
+        assert!(self.equation_size == equation.equation_size);
+        let mut source = ZeroOneLinearEquation::new(self.equation_size);
+        for i in 0..self.equations.len() {
+            if equation.get_i(self.highest_one_bit[i]) {
+                equation.xor(&self.equations[i]);
+                source.xor(&self.sources[i]);
+            }
+        }
+        if equation.highest_one_bit().is_some() {
+            return None;
+        }
+        Some((0..self.independent.len()).filter(|&k| source.get_i(k)).map(|k| self.independent[k]).collect())
+    }
+
+    /** Solve $Ax = b$ where the `i`th row of $A$ is the `i`th equation and $b_i$ is `rhs[i]`. It eliminates the augmented system with $b$ as the lowest bit in $O(m n^2 / w)$ time for $m$ equations, and returns `None` if the system is inconsistent. It panics once `compute_basis` or `insert_basis` (also by `merge`) has replaced the equations with the basis, since they are no longer the rows of $A$. */
+    pub fn solve(&self, rhs: &[bool]) -> Option<XorLinearEquationSolution> {
+        assert!(!self.reduced, "`solve` must be called before the equations are replaced by the basis.");
+        assert!(rhs.len() == self.equations.len());
+        let n = self.equation_size;
+        let mut augmented = XorLinearEquationSystem::new(self.equations.len(), n + 1);
+        for (i, (equation, &b)) in self.equations.iter().zip(rhs).enumerate() {
+            for j in 0..n {
+                if equation.get_i(j) {
+                    augmented.set_i_j(i, j + 1);
+                }
+            }
+            if b {
+                augmented.set_i_j(i, 0);
+            }
+        }
+        augmented.compute_basis();
+        if augmented.highest_one_bit.last() == Some(&0) {
+            return None;
+        }
+        let mut solution = ZeroOneLinearEquation::new(n);
+        let mut is_pivot = vec![false; n];
+        for (equation, &p) in augmented.equations.iter().zip(&augmented.highest_one_bit) {
+            is_pivot[p - 1] = true;
+            if equation.get_i(0) {
+                solution.set_i(p - 1);
+            }
+        }
+        let null_space = (0..n).filter(|&f| !is_pivot[f]).map(|f| {
+            let mut v = ZeroOneLinearEquation::new(n);
+            v.set_i(f);
+            for (equation, &p) in augmented.equations.iter().zip(&augmented.highest_one_bit) {
+                if equation.get_i(f + 1) {
+                    v.set_i(p - 1);
+                }
+            }
+            v
+        }).collect();
+        Some(XorLinearEquationSolution {
+            solution,
+            null_space,
+            rank: augmented.count_basis(),
+        })
+    }
+
+    /** Get the maximum equation that can be represented by the system. It is the all 0 equation if the basis is empty. */
     pub fn get_max(&self) -> ZeroOneLinearEquation{
-        let mut ans = self.equations[0].clone();
-        for i in 1..self.equations.len() {
-            if !ans.get_i(self.highest_one_bit[i]) {
-                ans.xor(&self.equations[i]);
+        self.get_max_from(ZeroOneLinearEquation::new(self.equation_size))
+    }
+
+    /** Get the maximum of `seed` xor an equation that can be represented by the system, by greedily setting the highest 1-bits of the basis from the highest. */
+    pub fn get_max_from(&self, mut seed: ZeroOneLinearEquation) -> ZeroOneLinearEquation {
+        assert!(self.equation_size == seed.equation_size);
+        for i in 0..self.equations.len() {
+            if !seed.get_i(self.highest_one_bit[i]) {
+                seed.xor(&self.equations[i]);
+            }
+        }
+        seed
+    }
+
+    /** Get the minimum non-zero equation that can be represented by the system, which is the basis equation with the lowest highest 1-bit. Return `None` if the basis is empty, where only the all 0 equation can be represented. */
+    pub fn get_min(&self) -> Option<ZeroOneLinearEquation> {
+        self.equations.last().cloned()
+    }
+
+    /** Get the `k`th (0-indexed) smallest equation that can be represented by the system, where the all 0 equation is the 0th. The $j$th bit of `k` decides whether the basis equation with the $j$th lowest highest 1-bit is taken. Return `None` if `k` is not smaller than `count_representable`. */
+    pub fn get_kth(&self, k: u128) -> Option<ZeroOneLinearEquation> {
+        let rank = self.equations.len();
+        if rank < 128 && k >> rank != 0 {
+            return None;
+        }
+        let mut ans = ZeroOneLinearEquation::new(self.equation_size);
+        for (j, equation) in self.equations.iter().rev().enumerate().take(128) {
+            if (k >> j) & 1 == 1 {
+                ans.xor(equation);
+            }
+        }
+        Some(ans)
+    }
+
+    /** Return the position of `equation` among the equations that can be represented by the system in increasing order (0-indexed), i.e. the inverse of `get_kth`, or `None` if it cannot be represented. The size of the basis must be at most 128. */
+    pub fn get_rank(&self, mut equation: ZeroOneLinearEquation) -> Option<u128> {
+        assert!(self.equation_size == equation.equation_size);
+        assert!(self.equations.len() <= 128, "The size of the basis must be at most 128.");
+        let mut k = 0u128;
+        for i in 0..self.equations.len() {
+            if equation.get_i(self.highest_one_bit[i]) {
+                equation.xor(&self.equations[i]);
+                k |= 1 << (self.equations.len() - 1 - i);
+            }
+        }
+        if equation.highest_one_bit().is_some() {
+            return None;
+        }
+        Some(k)
+    }
+
+    /** Return the number of distinct equations that can be represented by the system (including the all 0 equation), i.e. $2^r$ for the size $r$ of the basis, or `None` if it does not fit in `u128`. */
+    pub fn count_representable(&self) -> Option<u128> {
+        1u128.checked_shl(self.equations.len() as u32)
+    }
+
+    /** Merge the basis of `other` into the basis, so that it becomes the basis of the sum of the two subspaces. The basis equations of `other` are inserted by `insert_basis` (and numbered as such in `represent`), so it takes $O(r n^2 / w)$ time where $r$ is the size of the basis of `other`. */
+    pub fn merge(&mut self, other: &XorLinearEquationSystem) {
+        assert!(self.equation_size == other.equation_size);
+        for equation in &other.equations {
+            self.insert_basis(equation.clone());
+        }
+    }
+
+    /** Return the basis of the sum of the subspaces of the two bases. See `merge`. */
+    pub fn union(&self, other: &XorLinearEquationSystem) -> XorLinearEquationSystem {
+        let mut ans = self.clone();
+        ans.merge(other);
+        ans
+    }
+
+    /** Return the basis of the intersection of the subspaces of the two bases in $O((r_1 + r_2) n^2 / w)$ time. Each basis equation of `other` is reduced by the basis while tracking the part from the subspace of `self`, and if it is reduced to `0`, that part is in both subspaces. The resulting equations form a basis of the intersection since their number is $r_1 + r_2 - \dim(U + V)$. */
+    pub fn intersection(&self, other: &XorLinearEquationSystem) -> XorLinearEquationSystem {
+        assert!(self.equation_size == other.equation_size);
+        // slots[j] is an equation with the highest 1-bit j and its part from `self`
+        let mut slots: Vec<Option<(ZeroOneLinearEquation, ZeroOneLinearEquation)>> = vec![None; self.equation_size];
+        for (equation, &j) in self.equations.iter().zip(&self.highest_one_bit) {
+            slots[j] = Some((equation.clone(), equation.clone()));
+        }
+        let mut ans = XorLinearEquationSystem::new(0, self.equation_size);
+        for equation in &other.equations {
+            let mut x = equation.clone();
+            let mut part = ZeroOneLinearEquation::new(self.equation_size);
+            loop {
+                let Some(j) = x.highest_one_bit() else {
+                    ans.insert_basis(part);
+                    break;
+                };
+                if let Some((v, t)) = &slots[j] {
+                    x.xor(v);
+                    part.xor(t);
+                } else {
+                    slots[j] = Some((x, part));
+                    break;
+                }
             }
         }
-        return ans;
+        ans
+    }
+
+    /** Check if the subspace of the basis is contained in the subspace of `other` in $O(r_1 r_2 n / w)$ time. Both systems must hold the reduced bases produced by `compute_basis` or `insert_basis`, since it compares the sizes of the bases and reduces by the basis of `other`. */
+    pub fn is_subspace_of(&self, other: &XorLinearEquationSystem) -> bool {
+        assert!(self.equation_size == other.equation_size);
+        self.equations.len() <= other.equations.len() && self.equations.iter().all(|equation| other.can_represent(equation.clone()))
+    }
+
+    /** Check if the two bases span the same subspace. Both systems must hold the reduced bases produced by `compute_basis` or `insert_basis` (which `merge`, `union` and `intersection` also use), not the raw equations given before `compute_basis`. Such a basis of a subspace is unique (see `compute_basis`), so it only compares the equations in $O(r n / w)$ time. */
+    pub fn is_same_subspace(&self, other: &XorLinearEquationSystem) -> bool {
+        assert!(self.equation_size == other.equation_size);
+        self.equations == other.equations
     }
 
     /** Return the size of the basis. */
@@ -309,4 +499,275 @@ impl XorLinearEquationSystem {
     pub fn equation_size(&self) -> usize {
         self.equation_size
     }
-}
\ No newline at end of file
+}
+
+/** The position of an empty slot in `PrefixXorLinearEquationSystem`. */
+const NO_POSITION: u32 = u32::MAX;
+
+/** `PrefixXorLinearEquationSystem` is the prefix linear basis of a sequence of `ZeroOneLinearEquation`s $a_0, a_1, \dots$. After inserting $a_r$ it keeps the basis of $a_0, \dots, a_r$ in which the equation with the highest 1-bit $j$ comes from the latest possible position, so the equations of the basis from positions at least $l$ span exactly $a_l, \dots, a_r$. Each insertion takes $O(n^2 / w)$ time and stores a snapshot of $n \lceil n / 128 \rceil$ `u128`s and $n$ `u32` positions for $n$ dimensions, so `with_capacity` should be used when the length is known to avoid the peak of reallocation. Each range query is answered online on the stored snapshot in $O(n \lceil n / 128 \rceil)$ time, which is $O(n)$ for at most 128 dimensions. */
+#[derive(Clone, Debug)]
+pub struct PrefixXorLinearEquationSystem {
+    equation_size: usize,       // the size of dimension
+    words: usize,               // the number of u128 in an equation
+    basis: Vec<u128>,           // the basis after all the insertions, where the j-th equation has the highest 1-bit j
+    positions: Vec<u32>,        // the position of the j-th equation in the basis, or NO_POSITION
+    history: Vec<u128>,         // the basis after each insertion
+    history_positions: Vec<u32>,    // the positions after each insertion
+    len: usize,                 // the number of inserted equations
+}
+
+impl PrefixXorLinearEquationSystem {
+
+    /** New an empty `PrefixXorLinearEquationSystem` with `equation_size` of dimensions. */
+    pub fn new(equation_size: usize) -> Self {
+        PrefixXorLinearEquationSystem::with_capacity(equation_size, 0)
+    }
+
+    /** New an empty `PrefixXorLinearEquationSystem` with `equation_size` of dimensions and the memory reserved for `capacity` insertions. */
+    pub fn with_capacity(equation_size: usize, capacity: usize) -> Self {
+        let words = equation_size.div_ceil(128);
+        PrefixXorLinearEquationSystem {
+            equation_size,
+            words,
+            basis: vec![0u128; equation_size * words],
+            positions: vec![NO_POSITION; equation_size],
+            history: Vec::with_capacity(capacity * equation_size * words),
+            history_positions: Vec::with_capacity(capacity * equation_size),
+            len: 0,
+        }
+    }
+
+    /** New a `ZeroOneLinearEquation` with `equation_size` of dimensions. */
+    pub fn new_equation(&self) -> ZeroOneLinearEquation {
+        ZeroOneLinearEquation::new(self.equation_size)
+    }
+
+    /** Append `equation` to the sequence. Whenever it meets an equation of the basis from an earlier position with the same highest 1-bit, they are swapped and the earlier one continues to be inserted. */
+    pub fn insert_basis(&mut self, equation: ZeroOneLinearEquation) {
+        assert!(self.equation_size == equation.equation_size);
+        assert!(self.len() < NO_POSITION as usize);
+        let w = self.words;
+        let mut x = equation.f;
+        let mut position = self.len() as u32;
+        for j in (0..self.equation_size).rev() {
+            if (x[j >> 7] >> (j & 127)) & 1 == 0 {
+                continue;
+            }
+            let slot = &mut self.basis[j * w..(j + 1) * w];
+            let p = self.positions[j];
+            if p == NO_POSITION {
+                slot.copy_from_slice(&x);
+                self.positions[j] = position;
+                break;
+            }
+            if p < position {
+                slot.swap_with_slice(&mut x);
+                self.positions[j] = position;
+                position = p;
+            }
+            for (a, b) in x.iter_mut().zip(slot.iter()) {
+                *a ^= b;
+            }
+        }
+        self.history.extend_from_slice(&self.basis);
+        self.history_positions.extend_from_slice(&self.positions);
+        self.len += 1;
+    }
+
+    /** Return the basis equations of the prefix ending at `r` which come from positions at least `l`, from the highest 1-bit. */
+    fn range_basis(&self, l: usize, r: usize) -> impl Iterator<Item = (usize, &[u128])> {
+        assert!(l <= r && r < self.len());
+        let (n, w) = (self.equation_size, self.words);
+        let basis = &self.history[r * n * w..(r + 1) * n * w];
+        let positions = &self.history_positions[r * n..(r + 1) * n];
+        (0..n).rev().filter(move |&j| positions[j] != NO_POSITION && positions[j] as usize >= l).map(move |j| (j, &basis[j * w..(j + 1) * w]))
+    }
+
+    /** Get the maximum xor of a subset of the equations at positions $[l, r]$. */
+    pub fn get_max(&self, l: usize, r: usize) -> ZeroOneLinearEquation {
+        let mut ans = self.new_equation();
+        for (j, equation) in self.range_basis(l, r) {
+            if !ans.get_i(j) {
+                for (a, b) in ans.f.iter_mut().zip(equation) {
+                    *a ^= b;
+                }
+            }
+        }
+        ans
+    }
+
+    /** Check if the `equation` is the xor of a subset of the equations at positions $[l, r]$. */
+    pub fn can_represent(&self, l: usize, r: usize, mut equation: ZeroOneLinearEquation) -> bool {
+        assert!(self.equation_size == equation.equation_size);
+        for (j, basis) in self.range_basis(l, r) {
+            if equation.get_i(j) {
+                for (a, b) in equation.f.iter_mut().zip(basis) {
+                    *a ^= b;
+                }
+            }
+        }
+        equation.highest_one_bit().is_none()
+    }
+
+    /** Return the number of inserted equations. */
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /** Return whether no equation is inserted. */
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /** Return the size of the equation. */
+    pub fn equation_size(&self) -> usize {
+        self.equation_size
+    }
+}
+
+/** `XorBasisOperation` is an operation in the timeline of an `OfflineXorLinearEquationSystem`. */
+#[derive(Clone, Debug)]
+pub enum XorBasisOperation {
+    /** Insert an equation. */
+    Insert(ZeroOneLinearEquation),
+    /** Delete an equation equal to the given one, which must be inserted and not deleted yet. */
+    Delete(ZeroOneLinearEquation),
+    /** Query `can_represent` of the current equations. */
+    CanRepresent(ZeroOneLinearEquation),
+    /** Query `get_max` of the current equations. */
+    GetMax,
+}
+
+/** `XorBasisAnswer` is the answer to a query of an `OfflineXorLinearEquationSystem`. */
+#[derive(Clone, Debug, PartialEq)]
+pub enum XorBasisAnswer {
+    /** The answer to `XorBasisOperation::CanRepresent`. */
+    CanRepresent(bool),
+    /** The answer to `XorBasisOperation::GetMax`, which is the all 0 equation if there is no equation. */
+    GetMax(ZeroOneLinearEquation),
+}
+
+/** `OfflineXorLinearEquationSystem` answers the queries on a set of `ZeroOneLinearEquation`s under insertions and deletions offline. Every equation is alive in an interval of time, which is split into $O(\log T)$ nodes of a segment tree over the $T$ operations. A depth-first search over the tree inserts the equations of each node into a snapshot (a copy) of the basis of its parent, which is only taken for the nodes with equations, and every query is answered at its leaf. It takes $O(T \log T \cdot n^2 / w)$ time for $n$ dimensions, and the snapshots on the path of the search take $O(n^2 \log T / w)$ space. */
+#[derive(Clone, Debug)]
+pub struct OfflineXorLinearEquationSystem {
+    equation_size: usize,
+    operations: Vec<XorBasisOperation>,
+}
+
+impl OfflineXorLinearEquationSystem {
+
+    /** New an `OfflineXorLinearEquationSystem` with no operations and `equation_size` of dimensions. */
+    pub fn new(equation_size: usize) -> Self {
+        OfflineXorLinearEquationSystem {
+            equation_size,
+            operations: vec![],
+        }
+    }
+
+    /** New a `ZeroOneLinearEquation` with `equation_size` of dimensions. */
+    pub fn new_equation(&self) -> ZeroOneLinearEquation {
+        ZeroOneLinearEquation::new(self.equation_size)
+    }
+
+    /** Append an operation to the timeline. */
+    pub fn push(&mut self, operation: XorBasisOperation) {
+        match &operation {
+            XorBasisOperation::Insert(equation) | XorBasisOperation::Delete(equation) | XorBasisOperation::CanRepresent(equation) => {
+                assert!(self.equation_size == equation.equation_size);
+            },
+            XorBasisOperation::GetMax => {},
+        }
+        self.operations.push(operation);
+    }
+
+    /** Return the answers to the queries in the order of the timeline. */
+    pub fn solve(&self) -> Vec<XorBasisAnswer> {
+        let t = self.operations.len();
+        if t == 0 {
+            return vec![];
+        }
+        let mut tree = vec![vec![]; 4 * t];
+        let mut alive: HashMap<Vec<u128>, Vec<usize>> = HashMap::new();
+        for (i, operation) in self.operations.iter().enumerate() {
+            match operation {
+                XorBasisOperation::Insert(equation) => {
+                    alive.entry(equation.f.clone()).or_default().push(i);
+                },
+                XorBasisOperation::Delete(equation) => {
+                    let start = alive.get_mut(&equation.f).and_then(|v| v.pop()).expect("The deleted equation must be alive.");
+                    add_interval(&mut tree, 1, 0, t, start, i, start);
+                },
+                _ => {},
+            }
+        }
+        for starts in alive.values() {
+            for &start in starts {
+                add_interval(&mut tree, 1, 0, t, start, t, start);
+            }
+        }
+        let mut query_prefix = vec![0; t + 1];
+        for (i, operation) in self.operations.iter().enumerate() {
+            query_prefix[i + 1] = query_prefix[i] + matches!(operation, XorBasisOperation::CanRepresent(_) | XorBasisOperation::GetMax) as usize;
+        }
+        let mut answers = vec![];
+        let basis = XorLinearEquationSystem::new(0, self.equation_size);
+        self.dfs(&tree, &query_prefix, 1, 0, t, &basis, &mut answers);
+        answers
+    }
+
+    /** Answer the queries in $[l, r)$ with `basis` of the equations alive in the whole interval of the parent of `node`. */
+    #[allow(clippy::too_many_arguments)]
+    fn dfs(&self, tree: &[Vec<usize>], query_prefix: &[usize], node: usize, l: usize, r: usize, basis: &XorLinearEquationSystem, answers: &mut Vec<XorBasisAnswer>) {
+        if query_prefix[r] == query_prefix[l] {
+            return;
+        }
+        let snapshot;
+        let basis = if tree[node].is_empty() {
+            basis
+        } else {
+            let mut copy = basis.clone();
+            for &i in &tree[node] {
+                if let XorBasisOperation::Insert(equation) = &self.operations[i] {
+                    copy.insert_basis(equation.clone());
+                }
+            }
+            snapshot = copy;
+            &snapshot
+        };
+        if r - l == 1 {
+            match &self.operations[l] {
+                XorBasisOperation::CanRepresent(equation) => answers.push(XorBasisAnswer::CanRepresent(basis.can_represent(equation.clone()))),
+                XorBasisOperation::GetMax => answers.push(XorBasisAnswer::GetMax(basis.get_max())),
+                _ => {},
+            }
+            return;
+        }
+        let mid = (l + r) / 2;
+        self.dfs(tree, query_prefix, node * 2, l, mid, basis, answers);
+        self.dfs(tree, query_prefix, node * 2 + 1, mid, r, basis, answers);
+    }
+
+    /** Return the number of operations. */
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /** Return whether there is no operation. */
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+}
+
+/** Add the equation inserted at time `index` to the nodes covering $[ql, qr)$ in the segment tree over $[l, r)$. */
+fn add_interval(tree: &mut [Vec<usize>], node: usize, l: usize, r: usize, ql: usize, qr: usize, index: usize) {
+    if qr <= l || r <= ql {
+        return;
+    }
+    if ql <= l && r <= qr {
+        tree[node].push(index);
+        return;
+    }
+    let mid = (l + r) / 2;
+    add_interval(tree, node * 2, l, mid, ql, qr, index);
+    add_interval(tree, node * 2 + 1, mid, r, ql, qr, index);
+}
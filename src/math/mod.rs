@@ -4,4 +4,11 @@
 pub mod linearequation;
 pub mod prime;
 pub mod basic;
-pub mod bigint;
\ No newline at end of file
+pub mod integer;
+pub mod bigint;
+pub mod modint;
+pub mod convolution;
+pub mod discretelog;
+pub mod modroot;
+pub mod multiplicative;
+pub mod primalitycertificate;
\ No newline at end of file
@@ -0,0 +1,456 @@
+use std::{cell::Cell, fmt, iter::{Product, Sum}, ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign}, str::FromStr};
+use crate::math::basic::{add_mod_u64, sub_mod_u64, mul_mod_u32, mul_mod_u64, pow_mod_u64, inv_mod_u64, Barrett64};
+
+/** `ModInt` is an integer in $\mathbf{Z}_M$ where the modulus `M` is fixed at compile time. The value is always kept in $[0, M)$ and all the arithmetic is done by the routines in `math::basic`. */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ModInt<const M: u64> {
+    v: u64,
+}
+
+/** `ModInt998244353` is a `ModInt` with modulus $998244353 = 119 \cdot 2^{23} + 1$. */
+pub type ModInt998244353 = ModInt<998244353>;
+
+/** `ModInt1000000007` is a `ModInt` with modulus $10^9 + 7$. */
+pub type ModInt1000000007 = ModInt<1000000007>;
+
+impl<const M: u64> ModInt<M> {
+
+    /** New a `ModInt` with value `v` mod `M`. */
+    pub fn new(v: u64) -> Self {
+        ModInt { v: v % M }
+    }
+
+    /** New a `ModInt` with value `v` mod `M` where `v` can be negative. */
+    pub fn from_i64(v: i64) -> Self {
+        ModInt { v: (v as i128).rem_euclid(M as i128) as u64 }
+    }
+
+    /** Return the zero of $\mathbf{Z}_M$. */
+    pub fn zero() -> Self {
+        ModInt { v: 0 }
+    }
+
+    /** Return the one of $\mathbf{Z}_M$. */
+    pub fn one() -> Self {
+        ModInt::new(1)
+    }
+
+    /** Return the modulus `M`. */
+    pub fn modulus() -> u64 {
+        M
+    }
+
+    /** Return the value in $[0, M)$. */
+    pub fn value(&self) -> u64 {
+        self.v
+    }
+
+    /** Return whether the value is zero. */
+    pub fn is_zero(&self) -> bool {
+        self.v == 0
+    }
+
+    /** Return $self^e$ in $O(\log e)$ time. */
+    pub fn pow(&self, e: u64) -> Self {
+        ModInt { v: pow_mod_u64(&self.v, &e, &M) }
+    }
+
+    /** Return the multiplicative inverse by the extended Euclidean algorithm in $O(\log M)$ time. The value must be coprime to `M`. */
+    pub fn inv(&self) -> Self {
+        match inv_mod_u64(&self.v, &M) {
+            Some(v) => ModInt { v },
+            None => panic!("{} has no inverse modulo {}.", self.v, M),
+        }
+    }
+
+    /** Return the multiplicative inverse, or `None` if the value is not coprime to `M`. */
+    pub fn checked_inv(&self) -> Option<Self> {
+        inv_mod_u64(&self.v, &M).map(|v| ModInt { v })
+    }
+}
+
+impl<const M: u64> From<u32> for ModInt<M> {
+    fn from(v: u32) -> Self {
+        ModInt::new(v as u64)
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(v: u64) -> Self {
+        ModInt::new(v)
+    }
+}
+
+impl<const M: u64> From<usize> for ModInt<M> {
+    fn from(v: usize) -> Self {
+        ModInt::new(v as u64)
+    }
+}
+
+impl<const M: u64> From<i32> for ModInt<M> {
+    fn from(v: i32) -> Self {
+        ModInt::from_i64(v as i64)
+    }
+}
+
+impl<const M: u64> From<i64> for ModInt<M> {
+    fn from(v: i64) -> Self {
+        ModInt::from_i64(v)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        ModInt { v: add_mod_u64(&self.v, &other.v, &M) }
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        ModInt { v: sub_mod_u64(&self.v, &other.v, &M) }
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        if M <= u32::MAX as u64 {
+            return ModInt { v: mul_mod_u32(&(self.v as u32), &(other.v as u32), &(M as u32)) as u64 };
+        }
+        ModInt { v: mul_mod_u64(&self.v, &other.v, &M) }
+    }
+}
+
+impl<const M: u64> Div for ModInt<M> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        self * other.inv()
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        ModInt { v: sub_mod_u64(&0, &self.v, &M) }
+    }
+}
+
+impl<const M: u64> AddAssign for ModInt<M> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<const M: u64> SubAssign for ModInt<M> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<const M: u64> MulAssign for ModInt<M> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl<const M: u64> DivAssign for ModInt<M> {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+impl<const M: u64> Sum for ModInt<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(ModInt::zero(), |a, b| a + b)
+    }
+}
+
+impl<'a, const M: u64> Sum<&'a ModInt<M>> for ModInt<M> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(ModInt::zero(), |a, b| a + *b)
+    }
+}
+
+impl<const M: u64> Product for ModInt<M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(ModInt::one(), |a, b| a * b)
+    }
+}
+
+impl<'a, const M: u64> Product<&'a ModInt<M>> for ModInt<M> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(ModInt::one(), |a, b| a * *b)
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.v)
+    }
+}
+
+/** Parse a decimal integer (with an optional sign) of any length, reducing it digit by digit, so that inputs like $10^{100000}$ are accepted. */
+impl<const M: u64> FromStr for ModInt<M> {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        let s = s.trim();
+        let (neg, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() {
+            return Err(());
+        }
+        let ten = ModInt::new(10);
+        let mut ans = ModInt::zero();
+        for c in digits.bytes() {
+            if !c.is_ascii_digit() {
+                return Err(());
+            }
+            ans = ans * ten + ModInt::new((c - b'0') as u64);
+        }
+        Ok(if neg { -ans } else { ans })
+    }
+}
+
+thread_local! {
+    static DYN_MODULUS: Cell<Barrett64> = const { Cell::new(Barrett64::new(998244353)) };
+}
+
+/** `DynModInt` is an integer in $\mathbf{Z}_m$ where the modulus $m$ is chosen at runtime. The modulus is held by a thread-local `Barrett64` context (initially $998244353$) which is replaced by `DynModInt::set_modulus`, so that it can be changed per test case. Multiplications are done by Barrett reduction. All `DynModInt`s alive at the same time share the current modulus; values created under an old modulus must not be mixed with the new one. */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DynModInt {
+    v: u64,
+}
+
+impl DynModInt {
+
+    /** Set the modulus to `m` for the current thread. `m` must be positive. */
+    pub fn set_modulus(m: u64) {
+        DynModInt::set_context(Barrett64::new(m));
+    }
+
+    /** Set the modulus context for the current thread. */
+    pub fn set_context(context: Barrett64) {
+        DYN_MODULUS.with(|c| c.set(context));
+    }
+
+    /** Return the modulus context of the current thread. */
+    #[inline(always)]
+    pub fn context() -> Barrett64 {
+        DYN_MODULUS.with(|c| c.get())
+    }
+
+    /** Return the modulus of the current thread. */
+    pub fn modulus() -> u64 {
+        DynModInt::context().modulus()
+    }
+
+    /** New a `DynModInt` with value `v` mod the current modulus. */
+    pub fn new(v: u64) -> Self {
+        DynModInt { v: v % DynModInt::modulus() }
+    }
+
+    /** New a `DynModInt` with value `v` mod the current modulus where `v` can be negative. */
+    pub fn from_i64(v: i64) -> Self {
+        DynModInt { v: (v as i128).rem_euclid(DynModInt::modulus() as i128) as u64 }
+    }
+
+    /** Return the zero of $\mathbf{Z}_m$. */
+    pub fn zero() -> Self {
+        DynModInt { v: 0 }
+    }
+
+    /** Return the one of $\mathbf{Z}_m$. */
+    pub fn one() -> Self {
+        DynModInt::new(1)
+    }
+
+    /** Return the value in $[0, m)$. */
+    pub fn value(&self) -> u64 {
+        self.v
+    }
+
+    /** Return whether the value is zero. */
+    pub fn is_zero(&self) -> bool {
+        self.v == 0
+    }
+
+    /** Return $self^e$ in $O(\log e)$ time. */
+    pub fn pow(&self, e: u64) -> Self {
+        DynModInt { v: DynModInt::context().pow(self.v, e) }
+    }
+
+    /** Return the multiplicative inverse by the extended Euclidean algorithm in $O(\log m)$ time. The value must be coprime to the modulus. */
+    pub fn inv(&self) -> Self {
+        let m = DynModInt::modulus();
+        match inv_mod_u64(&self.v, &m) {
+            Some(v) => DynModInt { v },
+            None => panic!("{} has no inverse modulo {}.", self.v, m),
+        }
+    }
+
+    /** Return the multiplicative inverse, or `None` if the value is not coprime to the modulus. */
+    pub fn checked_inv(&self) -> Option<Self> {
+        inv_mod_u64(&self.v, &DynModInt::modulus()).map(|v| DynModInt { v })
+    }
+}
+
+impl From<u32> for DynModInt {
+    fn from(v: u32) -> Self {
+        DynModInt::new(v as u64)
+    }
+}
+
+impl From<u64> for DynModInt {
+    fn from(v: u64) -> Self {
+        DynModInt::new(v)
+    }
+}
+
+impl From<usize> for DynModInt {
+    fn from(v: usize) -> Self {
+        DynModInt::new(v as u64)
+    }
+}
+
+impl From<i32> for DynModInt {
+    fn from(v: i32) -> Self {
+        DynModInt::from_i64(v as i64)
+    }
+}
+
+impl From<i64> for DynModInt {
+    fn from(v: i64) -> Self {
+        DynModInt::from_i64(v)
+    }
+}
+
+impl Add for DynModInt {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        DynModInt { v: add_mod_u64(&self.v, &other.v, &DynModInt::modulus()) }
+    }
+}
+
+impl Sub for DynModInt {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        DynModInt { v: sub_mod_u64(&self.v, &other.v, &DynModInt::modulus()) }
+    }
+}
+
+impl Mul for DynModInt {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        DynModInt { v: DynModInt::context().mul(self.v, other.v) }
+    }
+}
+
+impl Div for DynModInt {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        self * other.inv()
+    }
+}
+
+impl Neg for DynModInt {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        DynModInt { v: sub_mod_u64(&0, &self.v, &DynModInt::modulus()) }
+    }
+}
+
+impl AddAssign for DynModInt {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for DynModInt {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign for DynModInt {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl DivAssign for DynModInt {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+impl Sum for DynModInt {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(DynModInt::zero(), |a, b| a + b)
+    }
+}
+
+impl<'a> Sum<&'a DynModInt> for DynModInt {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(DynModInt::zero(), |a, b| a + *b)
+    }
+}
+
+impl Product for DynModInt {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(DynModInt::one(), |a, b| a * b)
+    }
+}
+
+impl<'a> Product<&'a DynModInt> for DynModInt {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(DynModInt::one(), |a, b| a * *b)
+    }
+}
+
+impl fmt::Display for DynModInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.v)
+    }
+}
+
+/** Parse a decimal integer (with an optional sign) of any length modulo the current modulus. */
+impl FromStr for DynModInt {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        let s = s.trim();
+        let (neg, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() {
+            return Err(());
+        }
+        let ten = DynModInt::new(10);
+        let mut ans = DynModInt::zero();
+        for c in digits.bytes() {
+            if !c.is_ascii_digit() {
+                return Err(());
+            }
+            ans = ans * ten + DynModInt::new((c - b'0') as u64);
+        }
+        Ok(if neg { -ans } else { ans })
+    }
+}
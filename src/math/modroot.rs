@@ -0,0 +1,202 @@
+use crate::math::{basic::{gcd_u64, mul_mod_u64, pow_mod_u64, sub_mod_u64, inv_mod_u64, crt_u64}, discretelog::DiscreteLog, prime::ExtractPrimeFactors};
+
+/** `sqrt_mod_tonelli_shanks` returns the smaller square root of `a` modulo the prime `p` (the other one is $p - x$) by the Tonelli-Shanks algorithm, or `None` if `a` is a quadratic non-residue. It takes $O(\log^2 p)$ modular multiplications where $2^s \| p - 1$ contributes the $O(s^2)$ part. */
+pub fn sqrt_mod_tonelli_shanks(a: &u64, p: &u64) -> Option<u64> {
+    let (a, p) = (*a % *p, *p);
+    if p == 2 || a == 0 {
+        return Some(a);
+    }
+    if pow_mod_u64(&a, &((p - 1) / 2), &p) != 1 {
+        return None;
+    }
+    let s = (p - 1).trailing_zeros();
+    let q = (p - 1) >> s;
+    let z = (2..p).find(|&z| pow_mod_u64(&z, &((p - 1) / 2), &p) == p - 1).unwrap();
+    let mut m = s;
+    let mut c = pow_mod_u64(&z, &q, &p);
+    let mut t = pow_mod_u64(&a, &q, &p);
+    let mut r = pow_mod_u64(&a, &q.div_ceil(2), &p);
+    while t != 1 {
+        let mut i = 0;
+        let mut tt = t;
+        while tt != 1 {
+            tt = mul_mod_u64(&tt, &tt, &p);
+            i += 1;
+        }
+        let b = pow_mod_u64(&c, &(1 << (m - i - 1)), &p);
+        m = i;
+        c = mul_mod_u64(&b, &b, &p);
+        t = mul_mod_u64(&t, &c, &p);
+        r = mul_mod_u64(&r, &b, &p);
+    }
+    Some(r.min(p - r))
+}
+
+/** `sqrt_mod_cipolla` returns the smaller square root of `a` modulo the prime `p` (the other one is $p - x$) by Cipolla's algorithm, or `None` if `a` is a quadratic non-residue. It computes $(t + \sqrt{t^2 - a})^{(p+1)/2}$ in $\mathbf{F}_{p^2}$ in $O(\log p)$ multiplications after finding a $t$ such that $t^2 - a$ is a non-residue, which takes $2$ trials in expectation. */
+pub fn sqrt_mod_cipolla(a: &u64, p: &u64) -> Option<u64> {
+    let (a, p) = (*a % *p, *p);
+    if p == 2 || a == 0 {
+        return Some(a);
+    }
+    if pow_mod_u64(&a, &((p - 1) / 2), &p) != 1 {
+        return None;
+    }
+    let (t, w) = (0..p).map(|t| (t, sub_mod_u64(&mul_mod_u64(&t, &t, &p), &a, &p))).find(|&(_, w)| pow_mod_u64(&w, &((p - 1) / 2), &p) == p - 1).unwrap();
+    let mul = |(x1, y1): (u64, u64), (x2, y2): (u64, u64)| {
+        let x = (mul_mod_u64(&x1, &x2, &p) as u128 + mul_mod_u64(&mul_mod_u64(&y1, &y2, &p), &w, &p) as u128) % p as u128;
+        let y = (mul_mod_u64(&x1, &y2, &p) as u128 + mul_mod_u64(&x2, &y1, &p) as u128) % p as u128;
+        (x as u64, y as u64)
+    };
+    let mut ans = (1, 0);
+    let mut base = (t, 1);
+    let mut e = p.div_ceil(2);
+    while e > 0 {
+        if e & 1 == 1 {
+            ans = mul(ans, base);
+        }
+        base = mul(base, base);
+        e >>= 1;
+    }
+    Some(ans.0.min(p - ans.0))
+}
+
+/** Return the square roots of the unit `u` modulo $p^e$ (with $e \geq 1$) in increasing order. Odd primes lift a root modulo $p$ by Newton's iteration $x \to x - (x^2 - u)/(2x)$ which doubles the precision each time, and $p = 2$ fixes one bit at a time. */
+fn sqrt_unit_mod_prime_power(u: u64, p: u64, e: u32, pe: u64) -> Vec<u64> {
+    let mut roots = if p == 2 {
+        let x = match e {
+            1 => 1,
+            2 if u % 4 == 1 => 1,
+            _ if e >= 3 && u % 8 == 1 => {
+                let mut x = 1u128;
+                for j in 3..e {
+                    if (x * x).wrapping_sub(u as u128) & ((1 << (j + 1)) - 1) != 0 {
+                        x += 1 << (j - 1);
+                    }
+                }
+                x as u64
+            },
+            _ => return vec![],
+        };
+        let half = if e >= 2 { pe >> 1 } else { 0 };
+        vec![x, pe - x, (x + half) % pe, (pe - x + half) % pe]
+    } else {
+        let mut x = match sqrt_mod_tonelli_shanks(&u, &p) {
+            Some(x) => x,
+            None => return vec![],
+        };
+        let mut cur = 1;
+        while cur < e {
+            cur = (cur * 2).min(e);
+            let m = p.pow(cur);
+            let diff = sub_mod_u64(&mul_mod_u64(&x, &x, &m), &(u % m), &m);
+            let inv = inv_mod_u64(&((2 * x as u128 % m as u128) as u64), &m).unwrap();
+            x = sub_mod_u64(&x, &mul_mod_u64(&diff, &inv, &m), &m);
+        }
+        vec![x, (pe - x) % pe]
+    };
+    roots.sort_unstable();
+    roots.dedup();
+    roots
+}
+
+/** `sqrt_mod_prime_power` solves $x^2 \equiv a \pmod {p^k}$ for a prime `p` and `k` $\geq 1$ by Hensel lifting. It returns `(r, m)` such that the solutions in $[0, p^k)$ are exactly the $x \equiv r_i \pmod m$ for $r_i$ in the increasing `r` ($m$ can be smaller than $p^k$ when $p^2 \mid a$, e.g. every multiple of $p^{\lceil k/2 \rceil}$ is a root of $0$). `r` is empty if there is no solution. $p^k$ must fit in 64 bits. */
+pub fn sqrt_mod_prime_power(a: &u64, p: &u64, k: &u32) -> (Vec<u64>, u64) {
+    let (p, k) = (*p, *k);
+    assert!(k >= 1, "The exponent must be positive.");
+    let pk = p.checked_pow(k).expect("`p^k` must fit in 64 bits.");
+    let a = *a % pk;
+    if a == 0 {
+        return (vec![0], p.pow(k.div_ceil(2)));
+    }
+    let mut v = 0;
+    let mut u = a;
+    while u.is_multiple_of(p) {
+        u /= p;
+        v += 1;
+    }
+    if v % 2 == 1 {
+        return (vec![], pk);
+    }
+    let t = v / 2;
+    let e = k - v;
+    let roots = sqrt_unit_mod_prime_power(u, p, e, p.pow(e));
+    let m = p.pow(k - t);
+    (roots.iter().map(|&y| y * p.pow(t)).collect(), m)
+}
+
+/** `ModRoot` computes square roots modulo composite numbers and $k$-th roots modulo primes, using the given `ExtractPrimeFactors` implementor (e.g. `PollardRho`) for factorization. */
+#[derive(Clone, Debug)]
+pub struct ModRoot<F: ExtractPrimeFactors> {
+    factorizer: F,
+    discrete_log: DiscreteLog<F>,
+}
+
+impl<F: ExtractPrimeFactors> ModRoot<F> {
+    /** New a `ModRoot` with a given prime factor extractor `factorizer`. */
+    pub fn new(factorizer: &F) -> ModRoot<F> {
+        ModRoot {
+            factorizer: factorizer.clone(),
+            discrete_log: DiscreteLog::new(factorizer),
+        }
+    }
+
+    /** Solve $x^2 \equiv a \pmod n$ for a positive `n` by factorizing $n$, solving modulo every prime power by `sqrt_mod_prime_power` and combining the roots by the Chinese remainder theorem. It returns `(r, m)` such that the solutions in $[0, n)$ are exactly the $x \equiv r_i \pmod m$ for $r_i$ in the increasing `r`, where $m \mid n$. `r` is empty if there is no solution, and it has at most $2^{\omega(n)+1}$ elements. */
+    pub fn sqrt_mod(&mut self, a: &u64, n: &u64) -> (Vec<u64>, u64) {
+        assert!(*n > 0, "The modulus must be positive.");
+        let mut roots = vec![0u64];
+        let mut m = 1u64;
+        for &(p, k) in self.factorizer.factorize_u64(n).factors() {
+            let (r, mi) = sqrt_mod_prime_power(a, &p, &k);
+            if r.is_empty() {
+                return (vec![], *n);
+            }
+            roots = roots.iter().flat_map(|&x| r.iter().map(move |&y| crt_u64(&[(x, m), (y, mi)]).unwrap().0)).collect();
+            m *= mi;
+        }
+        roots.sort_unstable();
+        (roots, m)
+    }
+
+    /** Return an $x$ such that $x^k \equiv a \pmod p$ for a prime `p`, or `None` if there is no solution. With $d = \gcd(k, p - 1)$, all the solutions are $x$ times the $d$-th roots of unity. It reduces the problem to $d$-th roots and takes the $q$-th roots for the prime factors $q$ of $d$ one by one by the Adleman-Manders-Miller algorithm, so it takes $O(\sum_{q^e \| d} e \cdot s_q \sqrt{q})$ modular operations besides factorizing, where $q^{s_q} \| p - 1$. */
+    pub fn kth_root_mod_prime(&mut self, a: &u64, k: &u64, p: &u64) -> Option<u64> {
+        let (a, k, p) = (*a % *p, *k, *p);
+        if k == 0 {
+            return if a == 1 % p { Some(1 % p) } else { None };
+        }
+        if a == 0 || p == 2 {
+            return Some(a);
+        }
+        let n = p - 1;
+        let d = gcd_u64(k, n);
+        if pow_mod_u64(&a, &(n / d), &p) != 1 {
+            return None;
+        }
+        let e = inv_mod_u64(&(k / d % (n / d)), &(n / d)).unwrap();
+        let mut w = a;
+        for &(q, cnt) in self.factorizer.factorize_u64(&d).factors() {
+            for _ in 0..cnt {
+                w = self.qth_root(w, q, p);
+            }
+        }
+        Some(pow_mod_u64(&w, &e, &p))
+    }
+
+    /** Return a $q$-th root of the $q$-th power residue `a` modulo the prime `p` where the prime $q$ divides $p - 1$. */
+    fn qth_root(&mut self, a: u64, q: u64, p: u64) -> u64 {
+        let n = p - 1;
+        let mut t = n;
+        let mut qs = 1;
+        while t.is_multiple_of(q) {
+            t /= q;
+            qs *= q;
+        }
+        let alpha = (q - inv_mod_u64(&(t % q), &q).unwrap()) % q;
+        let r = pow_mod_u64(&a, &((t * alpha + 1) / q), &p);
+        let err = pow_mod_u64(&a, &(t * alpha), &p);
+        let rho = (2..p).find(|&x| pow_mod_u64(&x, &(n / q), &p) != 1).unwrap();
+        let c = pow_mod_u64(&rho, &t, &p);
+        let j = self.discrete_log.discrete_log_pohlig_hellman(&c, &err, &p).unwrap();
+        let z = pow_mod_u64(&c, &((qs - j / q) % qs), &p);
+        mul_mod_u64(&r, &z, &p)
+    }
+}
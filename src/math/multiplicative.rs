@@ -0,0 +1,127 @@
+use std::ops::{Add, Mul, Sub};
+
+/** `FloorBlocks` iterates over the maximal blocks $[l, r]$ of $[1, n]$ on which $\lfloor n/i \rfloor$ is constant, yielding `(l, r, n / l)`. There are $O(\sqrt{n})$ blocks. */
+#[derive(Clone, Debug)]
+pub struct FloorBlocks {
+    n: u64,
+    l: u64,
+}
+
+impl Iterator for FloorBlocks {
+    type Item = (u64, u64, u64);
+
+    fn next(&mut self) -> Option<(u64, u64, u64)> {
+        if self.l > self.n {
+            return None;
+        }
+        let l = self.l;
+        let q = self.n / l;
+        let r = self.n / q;
+        self.l = r + 1;
+        Some((l, r, q))
+    }
+}
+
+/** `floor_blocks` returns a `FloorBlocks` over $[1, n]$. */
+pub fn floor_blocks(n: u64) -> FloorBlocks {
+    FloorBlocks { n, l: 1 }
+}
+
+/** `min25_sieve` returns $\sum_{k=1}^{n} f(k)$ for a multiplicative $f$ by the Min_25 sieve in $O(n^{3/4} / \log n)$ time. `g(v)` must return $\sum_{p \leq v} f(p)$ over the primes for every $v$ of the form $\lfloor n/i \rfloor$ (e.g. by combining `LucyHedgehog`s when $f(p)$ is a polynomial in $p$), and `f(p, e)` must return $f(p^e)$. `T` can be any ring such as `u64`, `u128` or `ModInt`. */
+pub fn min25_sieve<T, G, F>(n: u64, g: G, f: F) -> T
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + From<u64>,
+    G: Fn(u64) -> T,
+    F: Fn(u64, u32) -> T,
+{
+    if n == 0 {
+        return T::from(0);
+    }
+    let r = n.isqrt() as usize;
+    let mut composite = vec![false; r + 1];
+    let mut primes = Vec::new();
+    for i in 2..=r {
+        if !composite[i] {
+            primes.push(i as u64);
+            for j in (i * i..=r).step_by(i) {
+                composite[j] = true;
+            }
+        }
+    }
+    T::from(1) + min25_rec(n, 0, &primes, &g, &f)
+}
+
+/** Return $\sum_{2 \leq k \leq x} f(k)$ over the $k$ whose smallest prime factor is at least `primes[j]`. */
+fn min25_rec<T, G, F>(x: u64, j: usize, primes: &[u64], g: &G, f: &F) -> T
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + From<u64>,
+    G: Fn(u64) -> T,
+    F: Fn(u64, u32) -> T,
+{
+    let last = if j == 0 { 1 } else { primes[j - 1] };
+    if last >= x {
+        return T::from(0);
+    }
+    let mut ans = g(x) - g(last);
+    for (k, &p) in primes.iter().enumerate().skip(j) {
+        if p * p > x {
+            break;
+        }
+        let mut pe = p;
+        let mut e = 1;
+        while pe * p <= x {
+            ans = ans + f(p, e) * min25_rec(x / pe, k + 1, primes, g, f) + f(p, e + 1);
+            pe *= p;
+            e += 1;
+        }
+    }
+    ans
+}
+
+/** `DuSieve` computes the prefix sums $S_f(x) = \sum_{k=1}^{x} f(k)$ for all $x$ of the form $\lfloor n/i \rfloor$ by Du's sieve, given an arithmetic function $g$ with $g(1) = 1$ such that the prefix sums of $g$ and $h = f * g$ (the Dirichlet convolution) are easy. It uses $S_f(x) = S_h(x) - \sum_{d=2}^{x} g(d) S_f(\lfloor x/d \rfloor)$ with memoization, which takes $O(n^{2/3})$ time when the prefix sums up to $n^{2/3}$ are given by a sieve (e.g. `Sieve`). */
+#[derive(Clone, Debug)]
+pub struct DuSieve<T, SG, SH> {
+    n: u64,
+    small: Vec<T>,
+    large: Vec<Option<T>>,
+    sg: SG,
+    sh: SH,
+}
+
+impl<T, SG, SH> DuSieve<T, SG, SH>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+    SG: Fn(u64) -> T,
+    SH: Fn(u64) -> T,
+{
+    /** New a `DuSieve` for $n$, where `small[x]` $= S_f(x)$ for all $x$ up to some $m$ (ideally about $n^{2/3}$), `sg(x)` $= \sum_{k=1}^{x} g(k)$ and `sh(x)` $= \sum_{k=1}^{x} h(k)$. `small` must cover at least $x = 1$. */
+    pub fn new(n: u64, small: Vec<T>, sg: SG, sh: SH) -> DuSieve<T, SG, SH> {
+        assert!(small.len() >= 2, "`small` must contain S_f(0) and S_f(1).");
+        let m = (small.len() - 1) as u64;
+        DuSieve {
+            n,
+            small,
+            large: vec![None; (n / (m + 1)) as usize + 1],
+            sg,
+            sh,
+        }
+    }
+
+    /** Return $S_f(x)$ where `x` must be of the form $\lfloor n/i \rfloor$. */
+    pub fn get(&mut self, x: u64) -> T {
+        if x < self.small.len() as u64 {
+            return self.small[x as usize];
+        }
+        let i = (self.n / x) as usize;
+        if let Some(v) = self.large[i] {
+            return v;
+        }
+        let mut ans = (self.sh)(x);
+        for (l, r, q) in floor_blocks(x).skip(1) {
+            let s = self.get(q);
+            ans = ans - ((self.sg)(r) - (self.sg)(l - 1)) * s;
+        }
+        self.large[i] = Some(ans);
+        ans
+    }
+}
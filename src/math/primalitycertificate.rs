@@ -0,0 +1,124 @@
+use crate::math::{basic::{gcd_u128, Montgomery128}, prime::ExtractPrimeFactors};
+
+/** The certificates of the integers below `SMALL_LIMIT` are empty, and such integers are checked by trial division. */
+const SMALL_LIMIT: u128 = 1 << 16;
+
+/** The largest base tried for each prime factor when generating a certificate. A prime has a valid base below it for every factor in practice, while a composite lacks one for some factor. */
+const WITNESS_LIMIT: u128 = 1 << 16;
+
+/** `PrimalityCertificate` is a Pocklington certificate (a Pratt certificate when all the prime factors of $n - 1$ are listed) of the primality of `n`. Let $F = \prod_i q_i^{e_i}$ over the listed `factors`. If $F \mid n - 1$, $F^2 > n$, every $q_i$ is a prime (proved by its own certificate recursively) and every $q_i$ has a base $a_i$ with $a_i^{n-1} \equiv 1 \pmod n$ and $\gcd(a_i^{(n-1)/q_i} - 1, n) = 1$, then every prime factor of $n$ is $1 \bmod F$, so $n$ is a prime. The fields are public so that the certificates can be stored and checked independently, and `verify` trusts nothing but the certificate itself. */
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PrimalityCertificate {
+    pub n: u128,
+    pub factors: Vec<CertificateFactor>,
+}
+
+/** `CertificateFactor` is a prime power $q^e \mid n - 1$ in a `PrimalityCertificate` together with its base `witness` and the `certificate` of $q$. */
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CertificateFactor {
+    pub q: u128,
+    pub e: u32,
+    pub witness: u128,
+    pub certificate: PrimalityCertificate,
+}
+
+impl PrimalityCertificate {
+    /** Generate a certificate for the 64-bit prime `n` by factorizing $n - 1$ with `factorizer`, or return `None` if `n` is not a prime. */
+    pub fn generate_u64<F: ExtractPrimeFactors>(factorizer: &mut F, n: &u64) -> Option<PrimalityCertificate> {
+        PrimalityCertificate::generate_u128(factorizer, &(*n as u128))
+    }
+
+    /** Generate a certificate for the 128-bit prime `n` by factorizing $n - 1$ with `factorizer`, or return `None` if `n` is not a prime. Only the largest prime powers of $n - 1$ are used until $F^2 > n$, and their primes are certified recursively. The certificate has $O(\log n)$ nodes on each level of the recursion. */
+    pub fn generate_u128<F: ExtractPrimeFactors>(factorizer: &mut F, n: &u128) -> Option<PrimalityCertificate> {
+        let n = *n;
+        if n < SMALL_LIMIT {
+            return if is_small_prime(n) { Some(PrimalityCertificate { n, factors: vec![] }) } else { None };
+        }
+        if n & 1 == 0 {
+            return None;
+        }
+        let mut factors = if n - 1 <= u64::MAX as u128 {
+            factorizer.factorize_u64(&((n - 1) as u64)).factors().iter().map(|&(q, e)| (q as u128, e)).collect()
+        } else {
+            factorizer.factorize_u128(&(n - 1)).factors().to_vec()
+        };
+        factors.sort_unstable_by_key(|&(q, e)| std::cmp::Reverse(q.pow(e)));
+        let mut f = 1u128;
+        let mut used = 0;
+        while f.checked_mul(f).is_some_and(|f2| f2 <= n) {
+            f *= factors[used].0.pow(factors[used].1);
+            used += 1;
+        }
+        factors.truncate(used);
+        factors.sort_unstable();
+        let mont = Montgomery128::new(n);
+        let one = mont.one();
+        let mut certificate = PrimalityCertificate { n, factors: Vec::with_capacity(used) };
+        for (q, e) in factors {
+            let mut witness = None;
+            for a in 2..WITNESS_LIMIT {
+                let x = mont.to_mont(a);
+                if mont.pow(x, n - 1) != one {
+                    return None;
+                }
+                if gcd_u128(mont.from_mont(mont.pow(x, (n - 1) / q)).checked_sub(1).unwrap_or(n - 1), n) == 1 {
+                    witness = Some(a);
+                    break;
+                }
+            }
+            certificate.factors.push(CertificateFactor {
+                q,
+                e,
+                witness: witness?,
+                certificate: PrimalityCertificate::generate_u128(factorizer, &q)?,
+            });
+        }
+        Some(certificate)
+    }
+
+    /** Check the certificate recursively. It returns `true` only if the certificate proves that `n` is a prime, which takes $O(\log^2 n)$ modular multiplications on each level. */
+    pub fn verify(&self) -> bool {
+        let n = self.n;
+        if self.factors.is_empty() {
+            return n < SMALL_LIMIT && is_small_prime(n);
+        }
+        if n < 3 || n & 1 == 0 {
+            return false;
+        }
+        let mont = Montgomery128::new(n);
+        let one = mont.one();
+        let mut f = 1u128;
+        let mut last = 1;
+        for factor in &self.factors {
+            let (q, a) = (factor.q, factor.witness);
+            // the primes must be distinct, or F would be overcounted
+            if q <= last || factor.certificate.n != q || a < 2 || a >= n {
+                return false;
+            }
+            last = q;
+            let Some(qe) = q.checked_pow(factor.e) else {
+                return false;
+            };
+            if factor.e == 0 || !(n - 1).is_multiple_of(qe) {
+                return false;
+            }
+            let Some(g) = f.checked_mul(qe) else {
+                return false;
+            };
+            f = g;
+            let x = mont.to_mont(a);
+            if mont.pow(x, n - 1) != one || gcd_u128(mont.from_mont(mont.pow(x, (n - 1) / q)).checked_sub(1).unwrap_or(n - 1), n) != 1 {
+                return false;
+            }
+            if !factor.certificate.verify() {
+                return false;
+            }
+        }
+        f.checked_mul(f).is_none_or(|f2| f2 > n)
+    }
+}
+
+/** Check whether `n` is a prime by trial division. */
+fn is_small_prime(n: u128) -> bool {
+    n >= 2 && (2..).take_while(|&d| d * d <= n).all(|d| !n.is_multiple_of(d))
+}
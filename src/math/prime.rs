@@ -1,4 +1,5 @@
-use crate::{random::generator::{Pseudorandom64, IntGenerator}, math::basic::{pow_mod_u32, pow_mod_u64, gcd_u32, gcd_u64}};
+use std::ops::{Add, Bound, Mul, Range, RangeBounds, Sub};
+use crate::{random::generator::{Pseudorandom64, IntGenerator}, math::{basic::{gcd_u32, gcd_u64, gcd_u128, lcm, Montgomery32, Montgomery64, Montgomery128}, integer::UnsignedInt}};
 
 /** `PrimalityTest` introduces a trait for primality test. */
 pub trait PrimalityTest: Clone {
@@ -7,6 +8,9 @@ pub trait PrimalityTest: Clone {
 
     /** Test if 64-bit `n` is a prime. */
     fn is_prime_u64(&mut self, n: &u64) -> bool;
+
+    /** Test if 128-bit `n` is a prime. */
+    fn is_prime_u128(&mut self, n: &u128) -> bool;
 }
 
 /** `FindPrimeFactors` introduces a trait for Extracting prime factors from an integer.*/
@@ -17,11 +21,146 @@ pub trait ExtractPrimeFactors: Clone {
     /** Extract all prime factors from an 64-bit integer. */
     fn extract_prime_factors_u64(&mut self, n: &u64) -> Vec<u64>;
 
+    /** Extract all prime factors from an 128-bit integer. */
+    fn extract_prime_factors_u128(&mut self, n: &u128) -> Vec<u128>;
+
     /** Extract a (not necessarily prime) factor (which is strictly smaller than `n`) from 32-bit `n`. */
     fn extract_factor_u32(&mut self, n: &u32) -> Option<u32>;
 
     /** Extract a (not necessarily prime) factor (which is strictly smaller than `n`) from 64-bit `n`. */
     fn extract_factor_u64(&mut self, n: &u64) -> Option<u64>;
+
+    /** Extract a (not necessarily prime) factor (which is strictly smaller than `n`) from 128-bit `n`. */
+    fn extract_factor_u128(&mut self, n: &u128) -> Option<u128>;
+
+    /** Factorize a positive 32-bit integer into a `Factorization`. */
+    fn factorize_u32(&mut self, n: &u32) -> Factorization<u32> {
+        Factorization::from_primes(&self.extract_prime_factors_u32(n))
+    }
+
+    /** Factorize a positive 64-bit integer into a `Factorization`. */
+    fn factorize_u64(&mut self, n: &u64) -> Factorization<u64> {
+        Factorization::from_primes(&self.extract_prime_factors_u64(n))
+    }
+
+    /** Factorize a positive 128-bit integer into a `Factorization`. */
+    fn factorize_u128(&mut self, n: &u128) -> Factorization<u128> {
+        Factorization::from_primes(&self.extract_prime_factors_u128(n))
+    }
+}
+
+/** `Factorization` is the prime factorization $n = \prod_i p_i^{e_i}$ of a positive integer as `(p_i, e_i)` pairs in increasing order of the primes. It provides the divisors and the common arithmetic functions of $n$. */
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Factorization<T: UnsignedInt> {
+    factors: Vec<(T, u32)>,
+}
+
+impl<T: UnsignedInt> Factorization<T> {
+    /** New a `Factorization` from a list of primes with repeats in any order (e.g. the result of `extract_prime_factors_u64`). */
+    pub fn from_primes(primes: &[T]) -> Factorization<T> {
+        let mut primes = primes.to_vec();
+        primes.sort_unstable();
+        let mut factors: Vec<(T, u32)> = Vec::new();
+        for p in primes {
+            match factors.last_mut() {
+                Some((q, e)) if *q == p => *e += 1,
+                _ => factors.push((p, 1)),
+            }
+        }
+        Factorization { factors }
+    }
+
+    /** Return the `(prime, exponent)` pairs in increasing order of the primes. */
+    pub fn factors(&self) -> &[(T, u32)] {
+        &self.factors
+    }
+
+    /** Return the distinct primes in increasing order. */
+    pub fn primes(&self) -> impl Iterator<Item = T> + '_ {
+        self.factors.iter().map(|&(p, _)| p)
+    }
+
+    /** Return $n$. */
+    pub fn value(&self) -> T {
+        self.factors.iter().fold(T::ONE, |acc, &(p, e)| (0..e).fold(acc, |acc, _| acc * p))
+    }
+
+    /** Return whether $n$ is a prime. */
+    pub fn is_prime(&self) -> bool {
+        self.factors.len() == 1 && self.factors[0].1 == 1
+    }
+
+    /** Return all the divisors of $n$ in increasing order. It takes $O(d(n) \log d(n))$ time. */
+    pub fn divisors(&self) -> impl Iterator<Item = T> {
+        let mut ans = vec![T::ONE];
+        for &(p, e) in &self.factors {
+            let len = ans.len();
+            let mut pk = T::ONE;
+            for _ in 0..e {
+                pk *= p;
+                for i in 0..len {
+                    ans.push(ans[i] * pk);
+                }
+            }
+        }
+        ans.sort_unstable();
+        ans.into_iter()
+    }
+
+    /** Return the number of divisors $d(n) = \prod_i (e_i + 1)$. */
+    pub fn divisor_count(&self) -> u64 {
+        self.factors.iter().map(|&(_, e)| e as u64 + 1).product()
+    }
+
+    /** Return the divisor function $\sigma_k(n) = \sum_{d \mid n} d^k = \prod_i \sum_{j=0}^{e_i} p_i^{jk}$, or `None` if it overflows. */
+    pub fn sigma(&self, k: u32) -> Option<T> {
+        let mut ans = T::ONE;
+        for &(p, e) in &self.factors {
+            let mut pk = T::ONE;
+            for _ in 0..k {
+                pk = pk.checked_mul(p)?;
+            }
+            let mut sum = T::ONE;
+            let mut cur = T::ONE;
+            for _ in 0..e {
+                cur = cur.checked_mul(pk)?;
+                sum = sum.checked_add(cur)?;
+            }
+            ans = ans.checked_mul(sum)?;
+        }
+        Some(ans)
+    }
+
+    /** Return Euler's totient $\varphi(n) = \prod_i p_i^{e_i - 1}(p_i - 1)$. */
+    pub fn phi(&self) -> T {
+        self.factors.iter().fold(T::ONE, |acc, &(p, e)| (1..e).fold(acc * (p - T::ONE), |acc, _| acc * p))
+    }
+
+    /** Return the Möbius function $\mu(n)$, which is $0$ if $n$ is not square-free and $(-1)^k$ for $k$ distinct prime factors otherwise. */
+    pub fn mobius(&self) -> i32 {
+        if self.factors.iter().any(|&(_, e)| e > 1) {
+            0
+        } else if self.factors.len().is_multiple_of(2) {
+            1
+        } else {
+            -1
+        }
+    }
+
+    /** Return the Carmichael function $\lambda(n)$, the exponent of the multiplicative group modulo $n$, which is the least common multiple of $\lambda(p_i^{e_i})$ where $\lambda(2^e) = 2^{e-2}$ for $e \geq 3$ and $\lambda(p^e) = \varphi(p^e)$ otherwise. */
+    pub fn carmichael_lambda(&self) -> T {
+        let two = T::ONE + T::ONE;
+        self.factors.iter().fold(T::ONE, |acc, &(p, e)| {
+            let e = if p == two && e >= 3 { e - 1 } else { e };
+            let l = (1..e).fold(p - T::ONE, |acc, _| acc * p);
+            lcm(acc, l).unwrap()
+        })
+    }
+
+    /** Return the radical $\mathrm{rad}(n) = \prod_i p_i$. */
+    pub fn radical(&self) -> T {
+        self.factors.iter().fold(T::ONE, |acc, &(p, _)| acc * p)
+    }
 }
 
 /** `BruteForcePrimalityTest` is a naive algorithm of primality test. Its computation complexity is $O(\sqrt{n})$. */
@@ -71,6 +210,24 @@ impl PrimalityTest for BruteForcePrimalityTest {
         }
         true
     }
+
+    /** Test if `n` is a prime in $O(\sqrt{n})$ time. It is only a fallback for 128-bit integers beyond 64 bits, which is practical only when `n` has a small factor. */
+    fn is_prime_u128(&mut self, n: &u128) -> bool {
+        if *n <= u64::MAX as u128 {
+            return self.is_prime_u64(&(*n as u64));
+        }
+        if *n & 1 == 0 {
+            return false;
+        }
+        let mut i = 3;
+        while i * i <= *n {
+            if (*n).is_multiple_of(i) {
+                return false;
+            }
+            i += 2;
+        }
+        true
+    }
 }
 
 impl ExtractPrimeFactors for BruteForcePrimalityTest {
@@ -157,6 +314,48 @@ impl ExtractPrimeFactors for BruteForcePrimalityTest {
         None
     }
 
+    /** Extract all prime factors from an 128-bit integer by trial division. It is only a fallback beyond 64 bits, which is practical only when at most one prime factor is large. */
+    fn extract_prime_factors_u128(&mut self, n: &u128) -> Vec<u128> {
+        if *n <= u64::MAX as u128 {
+            return self.extract_prime_factors_u64(&(*n as u64)).iter().map(|x| *x as u128).collect();
+        }
+        let mut ans = Vec::new();
+        let mut n = *n;
+        while n & 1 == 0 {
+            ans.push(2);
+            n >>= 1;
+        }
+        let mut i = 3;
+        while i * i <= n {
+            while n.is_multiple_of(i) {
+                ans.push(i);
+                n /= i;
+            }
+            i += 2;
+        }
+        if n != 1 {
+            ans.push(n);
+        }
+        ans
+    }
+
+    /** Extract a (not necessarily prime) factor (which is strictly smaller than `n`) from 128-bit `n` by trial division. */
+    fn extract_factor_u128(&mut self, n: &u128) -> Option<u128> {
+        if *n <= u64::MAX as u128 {
+            return self.extract_factor_u64(&(*n as u64)).map(|x| x as u128);
+        }
+        if *n & 1 == 0 {
+            return Some(2);
+        }
+        let mut i = 3;
+        while i * i <= *n {
+            if (*n).is_multiple_of(i) {
+                return Some(i);
+            }
+            i += 2;
+        }
+        None
+    }
 }
 
 /** `MillerRabin` is an efficient algorithm of primality test. Its computation complexity is $O(k\log^2 n)$ where $k$ is the number of rounds performed and $n$ is the number tested for primality. Its accuracy is $4^{-k}$. */
@@ -177,24 +376,28 @@ impl<RNG: Pseudorandom64> MillerRabin<RNG> {
 }
 
 impl<RNG: Pseudorandom64> PrimalityTest for MillerRabin<RNG> {
-    /** Test if 32-bit `n` is a prime. */
+    /** Test if 32-bit `n` is a prime. All the modular multiplications are done by `Montgomery32`. */
     fn is_prime_u32(&mut self, n: &u32) -> bool {
-        let mut expected_step:u64 = (((32 - n.leading_zeros()) * (32 - n.leading_zeros())) as u64) * (self.tests as u64); 
+        let expected_step:u64 = (((32 - n.leading_zeros()) * (32 - n.leading_zeros())) as u64) * (self.tests as u64); 
         if (*n as u64) <= expected_step * expected_step {
             return BruteForcePrimalityTest::new().is_prime_u32(n);
         }
+        if *n & 1 == 0 {
+            return false;
+        }
+        let mont = Montgomery32::new(*n);
+        let one = mont.one();
+        let n_1 = mont.to_mont(*n - 1);
         let mut d = *n - 1;
-        let n_1 = (*n-1) as u64; 
-        let nu64 = *n as u64;
         let mut s = 0;
         while d & 1 == 0 {
             d >>= 1;
             s += 1;
         }
         for _ in 0..self.tests {
-            let mut a = self.rng.gen_range_u32(2..*n);
-            let mut x = pow_mod_u32(&a, &d, &n) as u64;
-            if x == 1 || x == n_1 {
+            let a = self.rng.gen_range_u32(2..*n);
+            let mut x = mont.pow(mont.to_mont(a), d as u64);
+            if x == one || x == n_1 {
                 continue;
             }
             let mut i = 0;
@@ -203,7 +406,7 @@ impl<RNG: Pseudorandom64> PrimalityTest for MillerRabin<RNG> {
                     break;
                 }
                 i += 1;
-                x = x * x % nu64;
+                x = mont.mul(x, x);
             }
             if i == s {
                 return false;
@@ -212,23 +415,65 @@ impl<RNG: Pseudorandom64> PrimalityTest for MillerRabin<RNG> {
         true
     }
 
-    /** Test if 64-bit `n` is a prime. */
+    /** Test if 64-bit `n` is a prime. All the modular multiplications are done by `Montgomery64`. */
     fn is_prime_u64(&mut self, n: &u64) -> bool {
         if *n <= u32::MAX as u64 {
             return self.is_prime_u32(&(*n as u32));
         }
+        if *n & 1 == 0 {
+            return false;
+        }
+        let mont = Montgomery64::new(*n);
+        let one = mont.one();
+        let n_1 = mont.to_mont(*n - 1);
+        let mut d = *n - 1;
+        let mut s = 0;
+        while d & 1 == 0 {
+            d >>= 1;
+            s += 1;
+        }
+        for _ in 0..self.tests {
+            let a = self.rng.gen_range_u64(2..*n);
+            let mut x = mont.pow(mont.to_mont(a), d);
+            if x == one || x == n_1 {
+                continue;
+            }
+            let mut i = 0;
+            while i < s {
+                if x == n_1 {
+                    break;
+                }
+                i += 1;
+                x = mont.mul(x, x);
+            }
+            if i == s {
+                return false;
+            }
+        }
+        true
+    }
+
+    /** Test if 128-bit `n` is a prime. All the modular multiplications are done by `Montgomery128`. */
+    fn is_prime_u128(&mut self, n: &u128) -> bool {
+        if *n <= u64::MAX as u128 {
+            return self.is_prime_u64(&(*n as u64));
+        }
+        if *n & 1 == 0 {
+            return false;
+        }
+        let mont = Montgomery128::new(*n);
+        let one = mont.one();
+        let n_1 = mont.to_mont(*n - 1);
         let mut d = *n - 1;
-        let n_1 = (*n-1) as u128; 
-        let nu128 = *n as u128;
         let mut s = 0;
         while d & 1 == 0 {
             d >>= 1;
             s += 1;
         }
         for _ in 0..self.tests {
-            let mut a = self.rng.gen_range_u64(2..*n);
-            let mut x = pow_mod_u64(&a, &d, &n) as u128;
-            if x == 1 || x == n_1 {
+            let a = self.rng.gen_range_u128(2..*n);
+            let mut x = mont.pow(mont.to_mont(a), d);
+            if x == one || x == n_1 {
                 continue;
             }
             let mut i = 0;
@@ -237,7 +482,7 @@ impl<RNG: Pseudorandom64> PrimalityTest for MillerRabin<RNG> {
                     break;
                 }
                 i += 1;
-                x = x * x % nu128;
+                x = mont.mul(x, x);
             }
             if i == s {
                 return false;
@@ -248,6 +493,266 @@ impl<RNG: Pseudorandom64> PrimalityTest for MillerRabin<RNG> {
 }
 
 
+/** `DeterministicMillerRabin` is the Miller-Rabin primality test with fixed witnesses, so it needs no pseudorandom number generator and always gives the same answer. The witnesses $\{2, 7, 61\}$ are proven correct for all 32-bit integers and $\{2, 325, 9375, 28178, 450775, 9780504, 1795265022\}$ for all 64-bit integers. For 128-bit integers it uses the first $13$ primes as witnesses, which is proven correct below $3.3 \times 10^{24}$ and is only a strong probable prime test beyond. Its computation complexity is $O(k\log^2 n)$ for $k$ witnesses. */
+#[derive(Clone, Debug)]
+pub struct DeterministicMillerRabin {}
+
+impl DeterministicMillerRabin {
+    /** New a `DeterministicMillerRabin`. */
+    pub fn new() -> DeterministicMillerRabin {
+        DeterministicMillerRabin {}
+    }
+}
+
+impl Default for DeterministicMillerRabin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const WITNESSES_U32: [u32; 3] = [2, 7, 61];
+const WITNESSES_U64: [u64; 7] = [2, 325, 9375, 28178, 450775, 9780504, 1795265022];
+const WITNESSES_U128: [u128; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+impl PrimalityTest for DeterministicMillerRabin {
+    /** Test if 32-bit `n` is a prime with the witnesses $\{2, 7, 61\}$. All the modular multiplications are done by `Montgomery32`. */
+    fn is_prime_u32(&mut self, n: &u32) -> bool {
+        if *n < 64 {
+            return (1u64 << *n) & 0x28208a20a08a28ac != 0;
+        }
+        if *n & 1 == 0 {
+            return false;
+        }
+        let mont = Montgomery32::new(*n);
+        let one = mont.one();
+        let n_1 = mont.to_mont(*n - 1);
+        let s = (*n - 1).trailing_zeros();
+        let d = (*n - 1) >> s;
+        WITNESSES_U32.iter().all(|&a| {
+            if a % *n == 0 {
+                return true;
+            }
+            let mut x = mont.pow(mont.to_mont(a), d as u64);
+            if x == one || x == n_1 {
+                return true;
+            }
+            for _ in 1..s {
+                x = mont.mul(x, x);
+                if x == n_1 {
+                    return true;
+                }
+            }
+            false
+        })
+    }
+
+    /** Test if 64-bit `n` is a prime with the witnesses $\{2, 325, 9375, 28178, 450775, 9780504, 1795265022\}$. All the modular multiplications are done by `Montgomery64`. */
+    fn is_prime_u64(&mut self, n: &u64) -> bool {
+        if *n <= u32::MAX as u64 {
+            return self.is_prime_u32(&(*n as u32));
+        }
+        if *n & 1 == 0 {
+            return false;
+        }
+        let mont = Montgomery64::new(*n);
+        let one = mont.one();
+        let n_1 = mont.to_mont(*n - 1);
+        let s = (*n - 1).trailing_zeros();
+        let d = (*n - 1) >> s;
+        WITNESSES_U64.iter().all(|&a| {
+            if a % *n == 0 {
+                return true;
+            }
+            let mut x = mont.pow(mont.to_mont(a), d);
+            if x == one || x == n_1 {
+                return true;
+            }
+            for _ in 1..s {
+                x = mont.mul(x, x);
+                if x == n_1 {
+                    return true;
+                }
+            }
+            false
+        })
+    }
+
+    /** Test if 128-bit `n` is a prime with the first $13$ primes as witnesses, which is proven correct below $3.3 \times 10^{24}$. All the modular multiplications are done by `Montgomery128`. */
+    fn is_prime_u128(&mut self, n: &u128) -> bool {
+        if *n <= u64::MAX as u128 {
+            return self.is_prime_u64(&(*n as u64));
+        }
+        if *n & 1 == 0 {
+            return false;
+        }
+        let mont = Montgomery128::new(*n);
+        let one = mont.one();
+        let n_1 = mont.to_mont(*n - 1);
+        let s = (*n - 1).trailing_zeros();
+        let d = (*n - 1) >> s;
+        WITNESSES_U128.iter().all(|&a| {
+            let mut x = mont.pow(mont.to_mont(a), d);
+            if x == one || x == n_1 {
+                return true;
+            }
+            for _ in 1..s {
+                x = mont.mul(x, x);
+                if x == n_1 {
+                    return true;
+                }
+            }
+            false
+        })
+    }
+}
+
+/** `BailliePSW` is the Baillie-PSW primality test, i.e. a strong probable prime test to base $2$ followed by a strong Lucas probable prime test with the parameters $P = 1, Q = (1 - D)/4$ where $D$ is the first of $5, -7, 9, -11, \dots$ with the Jacobi symbol $(D/n) = -1$ (Selfridge's method A). It needs no random bases, it has been verified for all 64-bit integers, and no composite passing it is known. Its computation complexity is $O(\log^2 n)$. */
+#[derive(Clone, Debug)]
+pub struct BailliePSW {}
+
+impl BailliePSW {
+    /** New a `BailliePSW`. */
+    pub fn new() -> BailliePSW {
+        BailliePSW {}
+    }
+}
+
+impl Default for BailliePSW {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/** Return the Jacobi symbol $(a/n)$ for an odd positive `n`. */
+fn jacobi<T: UnsignedInt>(mut a: T, mut n: T) -> i32 {
+    let three = T::ONE + T::ONE + T::ONE;
+    let five = three + T::ONE + T::ONE;
+    let seven = five + T::ONE + T::ONE;
+    let eight = seven + T::ONE;
+    a %= n;
+    let mut ans = 1;
+    while a != T::ZERO {
+        while a & T::ONE == T::ZERO {
+            a >>= 1;
+            let r = n % eight;
+            if r == three || r == five {
+                ans = -ans;
+            }
+        }
+        std::mem::swap(&mut a, &mut n);
+        if a % (T::ONE + three) == three && n % (T::ONE + three) == three {
+            ans = -ans;
+        }
+        a %= n;
+    }
+    if n == T::ONE { ans } else { 0 }
+}
+
+macro_rules! impl_baillie_psw {
+    ($name:ident, $t:ty, $mont:ty) => {
+        /** Run the Baillie-PSW test on an odd `n` which has no prime factor below $64$. */
+        fn $name(n: $t) -> bool {
+            let mont = <$mont>::new(n);
+            let one = mont.one();
+            let n_1 = mont.to_mont(n - 1);
+            // strong probable prime test to base 2
+            let s = (n - 1).trailing_zeros();
+            let mut x = mont.pow(mont.to_mont(2), (n - 1) >> s);
+            if x != one && x != n_1 {
+                let mut i = 1;
+                while i < s {
+                    x = mont.mul(x, x);
+                    if x == n_1 {
+                        break;
+                    }
+                    i += 1;
+                }
+                if i >= s {
+                    return false;
+                }
+            }
+            // Selfridge's method A
+            let r = n.isqrt();
+            if r * r == n {
+                return false;
+            }
+            let mut d: i64 = 5;
+            loop {
+                let a = if d > 0 { d as $t } else { n - (d.unsigned_abs() as $t) % n };
+                match jacobi(a, n) {
+                    -1 => break,
+                    0 if d.unsigned_abs() as $t != n => return false,
+                    _ => d = if d > 0 { -d - 2 } else { -d + 2 },
+                }
+            }
+            let to_mont_signed = |v: i64| if v >= 0 { mont.to_mont(v as $t) } else { mont.sub(0, mont.to_mont(v.unsigned_abs() as $t)) };
+            let dm = to_mont_signed(d);
+            let q = to_mont_signed((1 - d) / 4);
+            let half = |x: $t| if x & 1 == 0 { x >> 1 } else { (x >> 1) + (n >> 1) + 1 };
+            // strong Lucas probable prime test
+            let s = n.trailing_ones();
+            let dd = ((n >> 1) + 1) >> (s - 1);
+            let (mut u, mut v, mut qk) = (one, one, q);
+            for i in (0..<$t>::BITS - 1 - dd.leading_zeros()).rev() {
+                u = mont.mul(u, v);
+                v = mont.sub(mont.mul(v, v), mont.add(qk, qk));
+                qk = mont.mul(qk, qk);
+                if (dd >> i) & 1 == 1 {
+                    let nu = half(mont.add(u, v));
+                    v = half(mont.add(mont.mul(dm, u), v));
+                    u = nu;
+                    qk = mont.mul(qk, q);
+                }
+            }
+            if u == 0 {
+                return true;
+            }
+            for _ in 0..s {
+                if v == 0 {
+                    return true;
+                }
+                v = mont.sub(mont.mul(v, v), mont.add(qk, qk));
+                qk = mont.mul(qk, qk);
+            }
+            false
+        }
+    };
+}
+
+impl_baillie_psw!(baillie_psw_u64, u64, Montgomery64);
+impl_baillie_psw!(baillie_psw_u128, u128, Montgomery128);
+
+const SMALL_PRIMES: [u64; 18] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61];
+
+impl PrimalityTest for BailliePSW {
+    /** Test if 32-bit `n` is a prime. */
+    fn is_prime_u32(&mut self, n: &u32) -> bool {
+        self.is_prime_u64(&(*n as u64))
+    }
+
+    /** Test if 64-bit `n` is a prime. All the modular multiplications are done by `Montgomery64`. */
+    fn is_prime_u64(&mut self, n: &u64) -> bool {
+        if *n < 64 {
+            return SMALL_PRIMES.contains(n);
+        }
+        if SMALL_PRIMES.iter().any(|&p| n.is_multiple_of(p)) {
+            return false;
+        }
+        baillie_psw_u64(*n)
+    }
+
+    /** Test if 128-bit `n` is a prime. All the modular multiplications are done by `Montgomery128`. */
+    fn is_prime_u128(&mut self, n: &u128) -> bool {
+        if *n <= u64::MAX as u128 {
+            return self.is_prime_u64(&(*n as u64));
+        }
+        if SMALL_PRIMES.iter().any(|&p| n.is_multiple_of(p as u128)) {
+            return false;
+        }
+        baillie_psw_u128(*n)
+    }
+}
+
 /** `PollardRho` is an algorithm for integer factorization. */
 #[derive(Clone, Debug)]
 pub struct PollardRho<RNG: Pseudorandom64, PT: PrimalityTest> {
@@ -306,7 +811,7 @@ impl<RNG: Pseudorandom64, PT: PrimalityTest> ExtractPrimeFactors for PollardRho<
         ans
     }
 
-    /** Extract a (not necessarily prime) factor (which is strictly smaller than `n`) from 32-bit `n`. If no such a factor then return `None`. It takes $O(n^{0.25})$ time.  */
+    /** Extract a (not necessarily prime) factor (which is strictly smaller than `n`) from 32-bit `n`. If no such a factor then return `None`. It takes $O(n^{0.25})$ time. The iteration $t \to t^2 + c$ runs in the Montgomery form of `Montgomery32`. */
     fn extract_factor_u32(&mut self, n: &u32) -> Option<u32> {
         if self.primality_tester.is_prime_u32(n) {
             return None;
@@ -317,46 +822,36 @@ impl<RNG: Pseudorandom64, PT: PrimalityTest> ExtractPrimeFactors for PollardRho<
         if *n & 1 == 0 {
             return Some(2);
         }
-        loop {
-            let mut s = 0u64;
-            let mut t = 0u64;
-            let c = self.rng.gen_range_u32(1..*n) as u64;
-            let n64 = *n as u64;
+        let mont = Montgomery32::new(*n);
+        'restart: loop {
+            let mut s = 0u32;
+            let mut t = 0u32;
+            let c = mont.to_mont(self.rng.gen_range_u32(1..*n));
             let mut goal = 1u64;
             loop {
-                let mut val = 1u64;
+                let mut val = mont.one();
                 for i in 1..=goal {
-                    t = (t * t) %n64 + c;
-                    if t >= n64 {
-                        t -= n64;
-                    }
-                    if t > s {
-                        val = val * (t - s) % n64;
-                    } else {
-                        val = val * (s - t) % n64;
-                    }
+                    t = mont.add(mont.mul(t, t), c);
+                    let diff = t.abs_diff(s);
+                    val = mont.mul(val, diff);
                     if val == 0 {
-                        if t != s {
-                            if t > s {
-                                return Some(gcd_u32((t - s) as u32, *n));
-                            } else {
-                                return Some(gcd_u32((s - t) as u32, *n));
-                            }
+                        if diff != 0 {
+                            return Some(gcd_u32(diff, *n));
                         }
-                        break;
+                        continue 'restart;
                     }
                     if i % 127 == 0 {
-                        let d = gcd_u32(val as u32, *n);
+                        let d = gcd_u32(val, *n);
                         if d > 1 {
                             return Some(d);
                         }
                     }
                 }    
-                let d = gcd_u32(val as u32, *n);
+                let d = gcd_u32(val, *n);
                 if d > 1 {
                     return Some(d);
                 }
-                s = t.clone();
+                s = t;
                 goal <<= 1;
             }
         }
@@ -405,7 +900,7 @@ impl<RNG: Pseudorandom64, PT: PrimalityTest> ExtractPrimeFactors for PollardRho<
         ans
     }
 
-    /** Extract a (not necessarily prime) factor (which is strictly smaller than `n`) from 64-bit `n`. If no such a factor then return `None`. It takes $O(n^{0.25})$ time.  */
+    /** Extract a (not necessarily prime) factor (which is strictly smaller than `n`) from 64-bit `n`. If no such a factor then return `None`. It takes $O(n^{0.25})$ time. The iteration $t \to t^2 + c$ runs in the Montgomery form of `Montgomery64`. */
     fn extract_factor_u64(&mut self, n: &u64) -> Option<u64> {
         if *n <= u32::MAX as u64 {
             return self.extract_factor_u32(&(*n as u32)).map(|x| x as u64);
@@ -416,48 +911,900 @@ impl<RNG: Pseudorandom64, PT: PrimalityTest> ExtractPrimeFactors for PollardRho<
         if *n & 1 == 0 {
             return Some(2);
         }
-        loop {
-            let mut s = 0u128;
-            let mut t = 0u128;
-            let c = self.rng.gen_range_u64(1..*n) as u128;
-            let n128 = *n as u128;
+        let mont = Montgomery64::new(*n);
+        'restart: loop {
+            let mut s = 0u64;
+            let mut t = 0u64;
+            let c = mont.to_mont(self.rng.gen_range_u64(1..*n));
             let mut goal = 1u64;
             loop {
-                let mut val = 1u128;
+                let mut val = mont.one();
                 for i in 1..=goal {
-                    t = (t * t) %n128 + c;
-                    if t >= n128 {
-                        t -= n128;
-                    }
-                    if t > s {
-                        val = val * (t - s) % n128;
-                    } else {
-                        val = val * (s - t) % n128;
-                    }
+                    t = mont.add(mont.mul(t, t), c);
+                    let diff = t.abs_diff(s);
+                    val = mont.mul(val, diff);
                     if val == 0 {
-                        if t != s {
-                            if t > s {
-                                return Some(gcd_u64((t - s) as u64, *n));
-                            } else {
-                                return Some(gcd_u64((s - t) as u64, *n));
-                            }
+                        if diff != 0 {
+                            return Some(gcd_u64(diff, *n));
                         }
-                        break;
+                        continue 'restart;
                     }
                     if i % 127 == 0 {
-                        let d = gcd_u64(val as u64, *n);
+                        let d = gcd_u64(val, *n);
                         if d > 1 {
                             return Some(d);
                         }
                     }
                 }    
-                let d = gcd_u64(val as u64, *n);
+                let d = gcd_u64(val, *n);
                 if d > 1 {
                     return Some(d);
                 }
-                s = t.clone();
+                s = t;
                 goal <<= 1;
             }
         }
     }
-}
\ No newline at end of file
+
+    /** Extract all prime factors from an 128-bit integer. */
+    fn extract_prime_factors_u128(&mut self, n: &u128) -> Vec<u128> {
+        if *n <= u64::MAX as u128 {
+            return self.extract_prime_factors_u64(&(*n as u64)).iter().map(|x| *x as u128).collect();
+        }
+        let mut ans = Vec::new();
+        let mut n = *n;
+        while n & 1 == 0 {
+            ans.push(2);
+            n >>= 1;
+        }
+        if n == 1 {
+            return ans;
+        }
+        let mut d = vec![n];
+        while let Some(mut x) = d.pop() {
+            match self.extract_factor_u128(&x) {
+                Some(p) => {
+                    let ret = self.extract_prime_factors_u128(&p);
+                    ans.append(&mut ret.clone());
+                    x /= p;
+                    for i in ret {
+                        while x.is_multiple_of(i) {
+                            ans.push(i);
+                            x /= i;
+                        }
+                        if x == 1 {
+                            break;
+                        }
+                    }
+                    if x != 1 {
+                        d.push(x);
+                    }
+                },
+                None => {
+                    ans.push(x);
+                },
+            }
+        }
+        ans
+    }
+
+    /** Extract a (not necessarily prime) factor (which is strictly smaller than `n`) from 128-bit `n`. If no such a factor then return `None`. It takes $O(n^{0.25})$ time, so it is slow for the products of two large primes. The iteration $t \to t^2 + c$ runs in the Montgomery form of `Montgomery128`. */
+    fn extract_factor_u128(&mut self, n: &u128) -> Option<u128> {
+        if *n <= u64::MAX as u128 {
+            return self.extract_factor_u64(&(*n as u64)).map(|x| x as u128);
+        }
+        if self.primality_tester.is_prime_u128(n) {
+            return None;
+        }
+        if *n & 1 == 0 {
+            return Some(2);
+        }
+        let mont = Montgomery128::new(*n);
+        'restart: loop {
+            let mut s = 0u128;
+            let mut t = 0u128;
+            let c = mont.to_mont(self.rng.gen_range_u128(1..*n));
+            let mut goal = 1u64;
+            loop {
+                let mut val = mont.one();
+                for i in 1..=goal {
+                    t = mont.add(mont.mul(t, t), c);
+                    let diff = t.abs_diff(s);
+                    val = mont.mul(val, diff);
+                    if val == 0 {
+                        if diff != 0 {
+                            return Some(gcd_u128(diff, *n));
+                        }
+                        continue 'restart;
+                    }
+                    if i % 127 == 0 {
+                        let d = gcd_u128(val, *n);
+                        if d > 1 {
+                            return Some(d);
+                        }
+                    }
+                }    
+                let d = gcd_u128(val, *n);
+                if d > 1 {
+                    return Some(d);
+                }
+                s = t;
+                goal <<= 1;
+            }
+        }
+    }
+}
+
+/** `ECM` is Lenstra's elliptic curve method for integer factorization on Montgomery curves $By^2 = x^3 + Ax^2 + x$ with Suyama's parameterization, using only the $x$ and $z$ coordinates. Each curve runs stage 1 (multiplying a point by all prime powers up to $B_1$ by the Montgomery ladder) and stage 2 (the baby-step giant-step continuation for a single prime in $(B_1, B_2]$ with $B_2 = 100B_1$). The bounds and the numbers of curves grow from $B_1 = 2000$ to $B_1 = 250000$, and a `PollardRho` built from the same `rng` and `primality_tester` is the fallback if all of them fail. The expected time depends on the smallest prime factor $p$ as $\exp((\sqrt{2} + o(1))\sqrt{\ln p \ln \ln p})$, so it finds $60$-bit factors of $128$-bit integers quickly. The integers fitting in $64$ bits are handed to that Pollard rho directly. */
+#[derive(Clone, Debug)]
+pub struct ECM<RNG: Pseudorandom64, PT: PrimalityTest> {
+    rng: IntGenerator<RNG>,
+    primality_tester: PT,
+    pollard_rho: PollardRho<RNG, PT>,
+    primes: Vec<u64>,       // the primes up to the largest B1
+    composite: Vec<bool>,   // the sieve up to B2 + D of the highest level reached so far
+}
+
+impl<RNG: Pseudorandom64, PT: PrimalityTest> ECM<RNG, PT> {
+    /** New an `ECM` with a given pseudorandom 64-bit number generator `rng` and a given primality tester `primality_tester`. The tables of primes are built when they are needed for the first time and kept for the later factorizations. */
+    pub fn new(rng: &RNG, primality_tester: &PT) -> ECM<RNG, PT> {
+        ECM {
+            rng: IntGenerator::new(rng),
+            primality_tester: primality_tester.clone(),
+            pollard_rho: PollardRho::new(rng, primality_tester),
+            primes: vec![],
+            composite: vec![],
+        }
+    }
+
+    /** Make sure that the sieve covers $[0, n]$, which happens at most once for each level. */
+    fn ensure_sieve(&mut self, n: usize) {
+        if self.composite.len() > n {
+            return;
+        }
+        let b1 = ECM_LEVELS[ECM_LEVELS.len() - 1].0;
+        self.composite = vec![false; n + 1];
+        self.primes.clear();
+        for i in 2..=n {
+            if !self.composite[i] {
+                if i as u64 <= b1 {
+                    self.primes.push(i as u64);
+                }
+                for j in (i * i..=n).step_by(i) {
+                    self.composite[j] = true;
+                }
+            }
+        }
+    }
+}
+
+const ECM_LEVELS: [(u64, u32); 4] = [(2000, 25), (11000, 90), (50000, 300), (250000, 700)];
+const ECM_D: u64 = 210;
+
+/** The Montgomery curve with $(A + 2)/4 = $ `num` / `den` in the Montgomery form of `mont`, where points are `(x, z)` pairs. */
+struct MontgomeryCurve {
+    mont: Montgomery128,
+    num: u128,
+    den: u128,
+}
+
+impl MontgomeryCurve {
+    /** Return $2P$. */
+    fn double(&self, (x, z): (u128, u128)) -> (u128, u128) {
+        let m = &self.mont;
+        let s = m.add(x, z);
+        let d = m.sub(x, z);
+        let t1 = m.mul(s, s);
+        let t2 = m.mul(d, d);
+        let t3 = m.sub(t1, t2);
+        (m.mul(m.mul(t1, t2), self.den), m.mul(t3, m.add(m.mul(self.den, t2), m.mul(self.num, t3))))
+    }
+
+    /** Return $P + Q$ given $P - Q$. */
+    fn add(&self, (xp, zp): (u128, u128), (xq, zq): (u128, u128), (xd, zd): (u128, u128)) -> (u128, u128) {
+        let m = &self.mont;
+        let u = m.mul(m.sub(xp, zp), m.add(xq, zq));
+        let v = m.mul(m.add(xp, zp), m.sub(xq, zq));
+        let s = m.add(u, v);
+        let d = m.sub(u, v);
+        (m.mul(zd, m.mul(s, s)), m.mul(xd, m.mul(d, d)))
+    }
+
+    /** Return $kP$ for $k \geq 1$ by the Montgomery ladder. */
+    fn mul(&self, p: (u128, u128), k: u64) -> (u128, u128) {
+        let mut r0 = p;
+        let mut r1 = self.double(p);
+        for i in (0..63 - k.leading_zeros()).rev() {
+            if (k >> i) & 1 == 1 {
+                r0 = self.add(r1, r0, p);
+                r1 = self.double(r1);
+            } else {
+                r1 = self.add(r0, r1, p);
+                r0 = self.double(r0);
+            }
+        }
+        r0
+    }
+}
+
+/** Run one curve with the parameter `sigma` on the odd `n`, and return a non-trivial factor if found. `primes` must contain the primes up to $B_1$ in increasing order and `composite` must mark the composites up to $B_2 + D$ at least. */
+fn ecm_curve(n: u128, sigma: u128, b1: u64, b2: u64, primes: &[u64], composite: &[bool]) -> Option<u128> {
+    let mont = Montgomery128::new(n);
+    let check = |g: u128| if g > 1 && g < n { Some(g) } else { None };
+    let s = mont.to_mont(sigma);
+    let u = mont.sub(mont.mul(s, s), mont.to_mont(5));
+    let v = mont.add(mont.add(s, s), mont.add(s, s));
+    let u3 = mont.mul(mont.mul(u, u), u);
+    let vu = mont.sub(v, u);
+    let num = mont.mul(mont.mul(mont.mul(vu, vu), vu), mont.add(mont.add(mont.add(u, u), u), v));
+    let den = mont.mul(mont.mul(u3, v), mont.to_mont(16));
+    if let Some(g) = check(gcd_u128(den, n)) {
+        return Some(g);
+    }
+    let curve = MontgomeryCurve { mont, num, den };
+    let mut q = (u3, mont.mul(mont.mul(v, v), v));
+    // stage 1
+    for &p in primes.iter().take_while(|&&p| p <= b1) {
+        let mut pk = p;
+        while pk <= b1 / p {
+            pk *= p;
+        }
+        q = curve.mul(q, pk);
+    }
+    let g = gcd_u128(q.1, n);
+    if g > 1 {
+        return check(g);
+    }
+    // stage 2
+    let half = ECM_D / 2;
+    let mut baby = vec![(0u128, 0u128); half as usize + 1];
+    let q2 = curve.double(q);
+    baby[1] = q;
+    baby[3] = curve.add(q2, q, q);
+    for j in (5..=half).step_by(2) {
+        baby[j as usize] = curve.add(baby[j as usize - 2], q2, baby[j as usize - 4]);
+    }
+    let qd = curve.mul(q, ECM_D);
+    let m0 = (b1 / ECM_D).max(1);
+    let mut prev = curve.mul(q, (m0 - 1).max(1) * ECM_D);
+    let mut cur = curve.mul(q, m0 * ECM_D);
+    let mut acc = mont.one();
+    for m in m0..=b2 / ECM_D + 1 {
+        let c = m * ECM_D;
+        for j in (1..=half).step_by(2) {
+            if (c - j > b1 && !composite[(c - j) as usize]) || (c + j <= b2 && !composite[(c + j) as usize]) {
+                let (xs, zs) = baby[j as usize];
+                acc = mont.mul(acc, mont.sub(mont.mul(cur.0, zs), mont.mul(xs, cur.1)));
+            }
+        }
+        let next = if m == 1 { curve.double(cur) } else { curve.add(cur, qd, prev) };
+        prev = cur;
+        cur = next;
+    }
+    check(gcd_u128(acc, n))
+}
+
+impl<RNG: Pseudorandom64, PT: PrimalityTest> ExtractPrimeFactors for ECM<RNG, PT> {
+    /** Extract all prime factors from an 32-bit integer by the Pollard rho. */
+    fn extract_prime_factors_u32(&mut self, n: &u32) -> Vec<u32> {
+        self.pollard_rho.extract_prime_factors_u32(n)
+    }
+
+    /** Extract all prime factors from an 64-bit integer by the Pollard rho. */
+    fn extract_prime_factors_u64(&mut self, n: &u64) -> Vec<u64> {
+        self.pollard_rho.extract_prime_factors_u64(n)
+    }
+
+    /** Extract all prime factors from an 128-bit integer, splitting the composite factors by `extract_factor_u128` recursively. */
+    fn extract_prime_factors_u128(&mut self, n: &u128) -> Vec<u128> {
+        if *n <= u64::MAX as u128 {
+            return self.extract_prime_factors_u64(&(*n as u64)).iter().map(|x| *x as u128).collect();
+        }
+        let mut ans = Vec::new();
+        let mut d = vec![*n];
+        while let Some(x) = d.pop() {
+            match self.extract_factor_u128(&x) {
+                Some(p) => {
+                    d.push(p);
+                    d.push(x / p);
+                },
+                None => {
+                    if x > 1 {
+                        ans.push(x);
+                    }
+                },
+            }
+        }
+        ans
+    }
+
+    /** Extract a (not necessarily prime) factor (which is strictly smaller than `n`) from 32-bit `n` by the Pollard rho. */
+    fn extract_factor_u32(&mut self, n: &u32) -> Option<u32> {
+        self.pollard_rho.extract_factor_u32(n)
+    }
+
+    /** Extract a (not necessarily prime) factor (which is strictly smaller than `n`) from 64-bit `n` by the Pollard rho. */
+    fn extract_factor_u64(&mut self, n: &u64) -> Option<u64> {
+        self.pollard_rho.extract_factor_u64(n)
+    }
+
+    /** Extract a (not necessarily prime) factor (which is strictly smaller than `n`) from 128-bit `n` by trying the small primes, square roots and then curves of growing bounds, with the Pollard rho as the last resort. If no such a factor then return `None`. */
+    fn extract_factor_u128(&mut self, n: &u128) -> Option<u128> {
+        if *n <= u64::MAX as u128 {
+            return self.extract_factor_u64(&(*n as u64)).map(|x| x as u128);
+        }
+        let n = *n;
+        if n & 1 == 0 {
+            return Some(2);
+        }
+        if self.primality_tester.is_prime_u128(&n) {
+            return None;
+        }
+        for p in (3..1000).step_by(2) {
+            if n.is_multiple_of(p) {
+                return Some(p);
+            }
+        }
+        let r = n.isqrt();
+        if r * r == n {
+            return Some(r);
+        }
+        for (b1, curves) in ECM_LEVELS {
+            let b2 = b1 * 100;
+            self.ensure_sieve((b2 + ECM_D) as usize);
+            for _ in 0..curves {
+                let sigma = self.rng.gen_range_u64(6..u64::MAX) as u128 % n;
+                if let Some(g) = ecm_curve(n, sigma, b1, b2, &self.primes, &self.composite) {
+                    return Some(g);
+                }
+            }
+        }
+        self.pollard_rho.extract_factor_u128(&n)
+    }
+}
+
+/** `Sieve` is the linear sieve (the sieve of Euler) up to $N$ in $O(N)$ time, which stores the smallest prime factor of every integer in $[0, N]$ and all primes up to $N$. The tables of $\varphi$, $\mu$ and the divisor count can be computed in $O(N)$ time on demand. It answers the primality tests in $O(1)$ time and the factorizations in $O(\log n)$ time for $n \leq N$, and it falls back to the given `fallback` above $N$. */
+#[derive(Clone, Debug)]
+pub struct Sieve<F: Clone> {
+    n: usize,
+    spf: Vec<u32>,
+    primes: Vec<u32>,
+    phi: Vec<u32>,
+    mu: Vec<i8>,
+    divisor_count: Vec<u32>,
+    fallback: F,
+}
+
+impl<F: Clone> Sieve<F> {
+    /** New a `Sieve` up to `n` with a given `fallback` for the integers larger than `n`. `n` must be smaller than $2^{32}$. */
+    pub fn new(n: usize, fallback: &F) -> Sieve<F> {
+        assert!(n <= u32::MAX as usize, "The limit of the sieve must be smaller than 2^32.");
+        let mut spf = vec![0u32; n + 1];
+        let mut primes = Vec::new();
+        for i in 2..=n {
+            if spf[i] == 0 {
+                spf[i] = i as u32;
+                primes.push(i as u32);
+            }
+            for &p in &primes {
+                if p > spf[i] || i * p as usize > n {
+                    break;
+                }
+                spf[i * p as usize] = p;
+            }
+        }
+        Sieve {
+            n,
+            spf,
+            primes,
+            phi: Vec::new(),
+            mu: Vec::new(),
+            divisor_count: Vec::new(),
+            fallback: fallback.clone(),
+        }
+    }
+
+    /** Return the limit $N$. */
+    pub fn limit(&self) -> usize {
+        self.n
+    }
+
+    /** Return all primes up to $N$ in increasing order. */
+    pub fn primes(&self) -> &[u32] {
+        &self.primes
+    }
+
+    /** Return the smallest prime factor of `x` $\in [2, N]$. */
+    pub fn smallest_prime_factor(&self, x: usize) -> u32 {
+        assert!((2..=self.n).contains(&x), "`x` must be in [2, N].");
+        self.spf[x]
+    }
+
+    /** Compute the table of Euler's totient $\varphi$ up to $N$ in $O(N)$ time. */
+    pub fn compute_phi(&mut self) {
+        let mut phi = vec![0u32; self.n + 1];
+        if self.n >= 1 {
+            phi[1] = 1;
+        }
+        for i in 2..=self.n {
+            let p = self.spf[i];
+            let m = i / p as usize;
+            phi[i] = if self.spf[m] == p { phi[m] * p } else { phi[m] * (p - 1) };
+        }
+        self.phi = phi;
+    }
+
+    /** Compute the table of the Möbius function $\mu$ up to $N$ in $O(N)$ time. */
+    pub fn compute_mu(&mut self) {
+        let mut mu = vec![0i8; self.n + 1];
+        if self.n >= 1 {
+            mu[1] = 1;
+        }
+        for i in 2..=self.n {
+            let p = self.spf[i];
+            let m = i / p as usize;
+            mu[i] = if self.spf[m] == p { 0 } else { -mu[m] };
+        }
+        self.mu = mu;
+    }
+
+    /** Compute the table of the divisor count $d$ up to $N$ in $O(N)$ time. */
+    pub fn compute_divisor_count(&mut self) {
+        let mut d = vec![0u32; self.n + 1];
+        let mut e = vec![0u32; self.n + 1];
+        if self.n >= 1 {
+            d[1] = 1;
+        }
+        for i in 2..=self.n {
+            let p = self.spf[i];
+            let m = i / p as usize;
+            if self.spf[m] == p {
+                e[i] = e[m] + 1;
+                d[i] = d[m] / (e[m] + 1) * (e[i] + 1);
+            } else {
+                e[i] = 1;
+                d[i] = d[m] * 2;
+            }
+        }
+        self.divisor_count = d;
+    }
+
+    /** Return $\varphi(x)$ for `x` $\in [1, N]$. `compute_phi` must be called before. */
+    pub fn phi(&self, x: usize) -> u32 {
+        assert!(!self.phi.is_empty(), "`compute_phi` must be called before.");
+        self.phi[x]
+    }
+
+    /** Return $\mu(x)$ for `x` $\in [1, N]$. `compute_mu` must be called before. */
+    pub fn mu(&self, x: usize) -> i8 {
+        assert!(!self.mu.is_empty(), "`compute_mu` must be called before.");
+        self.mu[x]
+    }
+
+    /** Return the number of divisors of `x` $\in [1, N]$. `compute_divisor_count` must be called before. */
+    pub fn divisor_count(&self, x: usize) -> u32 {
+        assert!(!self.divisor_count.is_empty(), "`compute_divisor_count` must be called before.");
+        self.divisor_count[x]
+    }
+
+    /** Return the prime factors of `x` $\in [1, N]$ with repeats in increasing order in $O(\log x)$ time. */
+    fn factors_in_table(&self, mut x: usize) -> Vec<u32> {
+        let mut ans = Vec::new();
+        while x > 1 {
+            let p = self.spf[x];
+            ans.push(p);
+            x /= p as usize;
+        }
+        ans
+    }
+}
+
+impl<F: PrimalityTest> PrimalityTest for Sieve<F> {
+    /** Test if 32-bit `n` is a prime in $O(1)$ time if $n \leq N$, otherwise by the fallback. */
+    fn is_prime_u32(&mut self, n: &u32) -> bool {
+        if (*n as usize) <= self.n {
+            return *n >= 2 && self.spf[*n as usize] == *n;
+        }
+        self.fallback.is_prime_u32(n)
+    }
+
+    /** Test if 64-bit `n` is a prime in $O(1)$ time if $n \leq N$, otherwise by the fallback. */
+    fn is_prime_u64(&mut self, n: &u64) -> bool {
+        if *n <= self.n as u64 {
+            return self.is_prime_u32(&(*n as u32));
+        }
+        self.fallback.is_prime_u64(n)
+    }
+
+    /** Test if 128-bit `n` is a prime in $O(1)$ time if $n \leq N$, otherwise by the fallback. */
+    fn is_prime_u128(&mut self, n: &u128) -> bool {
+        if *n <= self.n as u128 {
+            return self.is_prime_u32(&(*n as u32));
+        }
+        self.fallback.is_prime_u128(n)
+    }
+}
+
+impl<F: ExtractPrimeFactors> ExtractPrimeFactors for Sieve<F> {
+    /** Extract all prime factors from an 32-bit integer in $O(\log n)$ time if $n \leq N$, otherwise by the fallback. */
+    fn extract_prime_factors_u32(&mut self, n: &u32) -> Vec<u32> {
+        if (*n as usize) <= self.n {
+            return self.factors_in_table(*n as usize);
+        }
+        self.fallback.extract_prime_factors_u32(n)
+    }
+
+    /** Extract all prime factors from an 64-bit integer in $O(\log n)$ time if $n \leq N$, otherwise by the fallback. */
+    fn extract_prime_factors_u64(&mut self, n: &u64) -> Vec<u64> {
+        if *n <= self.n as u64 {
+            return self.factors_in_table(*n as usize).iter().map(|&p| p as u64).collect();
+        }
+        self.fallback.extract_prime_factors_u64(n)
+    }
+
+    /** Extract all prime factors from an 128-bit integer in $O(\log n)$ time if $n \leq N$, otherwise by the fallback. */
+    fn extract_prime_factors_u128(&mut self, n: &u128) -> Vec<u128> {
+        if *n <= self.n as u128 {
+            return self.factors_in_table(*n as usize).iter().map(|&p| p as u128).collect();
+        }
+        self.fallback.extract_prime_factors_u128(n)
+    }
+
+    /** Extract the smallest prime factor from a composite 32-bit `n` in $O(1)$ time if $n \leq N$, otherwise by the fallback. */
+    fn extract_factor_u32(&mut self, n: &u32) -> Option<u32> {
+        if (*n as usize) <= self.n {
+            return if *n >= 2 && self.spf[*n as usize] != *n { Some(self.spf[*n as usize]) } else { None };
+        }
+        self.fallback.extract_factor_u32(n)
+    }
+
+    /** Extract the smallest prime factor from a composite 64-bit `n` in $O(1)$ time if $n \leq N$, otherwise by the fallback. */
+    fn extract_factor_u64(&mut self, n: &u64) -> Option<u64> {
+        if *n <= self.n as u64 {
+            return self.extract_factor_u32(&(*n as u32)).map(|p| p as u64);
+        }
+        self.fallback.extract_factor_u64(n)
+    }
+
+    /** Extract the smallest prime factor from a composite 128-bit `n` in $O(1)$ time if $n \leq N$, otherwise by the fallback. */
+    fn extract_factor_u128(&mut self, n: &u128) -> Option<u128> {
+        if *n <= self.n as u128 {
+            return self.extract_factor_u32(&(*n as u32)).map(|p| p as u128);
+        }
+        self.fallback.extract_factor_u128(n)
+    }
+}
+
+/** `SegmentedSieve` is the sieve of Eratosthenes on an interval $[L, R]$, which marks the multiples of the base primes up to $\sqrt{R}$ in a window of $R - L + 1$ integers. It takes $O((R - L) \log \log R + \sqrt{R})$ time and $O(R - L + \sqrt{R})$ space, so it works for large $R$ such as $10^{12}$ as long as the window is short. */
+#[derive(Clone, Debug)]
+pub struct SegmentedSieve {
+    l: u64,
+    r: u64,
+    base_primes: Vec<u64>,
+    is_prime: Vec<bool>,
+}
+
+impl SegmentedSieve {
+    /** New a `SegmentedSieve` on $[l, r]$. `l` must not be larger than `r`. */
+    pub fn new(l: u64, r: u64) -> SegmentedSieve {
+        assert!(l <= r, "`l` must not be larger than `r`.");
+        let m = r.isqrt() as usize;
+        let mut composite = vec![false; m + 1];
+        let mut base_primes = Vec::new();
+        for i in 2..=m {
+            if !composite[i] {
+                base_primes.push(i as u64);
+                for j in (i * i..=m).step_by(i) {
+                    composite[j] = true;
+                }
+            }
+        }
+        let mut is_prime = vec![true; (r - l + 1) as usize];
+        for x in l..=r.min(1) {
+            is_prime[(x - l) as usize] = false;
+        }
+        for &p in &base_primes {
+            let Some(start) = l.div_ceil(p).checked_mul(p) else {
+                continue;
+            };
+            let mut x = (p * p).max(start);
+            while x <= r {
+                is_prime[(x - l) as usize] = false;
+                x = match x.checked_add(p) {
+                    Some(x) => x,
+                    None => break,
+                };
+            }
+        }
+        SegmentedSieve { l, r, base_primes, is_prime }
+    }
+
+    /** Return whether `x` $\in [L, R]$ is a prime in $O(1)$ time. */
+    pub fn is_prime(&self, x: u64) -> bool {
+        assert!(self.l <= x && x <= self.r, "`x` must be in [L, R].");
+        self.is_prime[(x - self.l) as usize]
+    }
+
+    /** Return all primes in $[L, R]$ in increasing order. */
+    pub fn primes(&self) -> impl Iterator<Item = u64> + '_ {
+        self.is_prime.iter().enumerate().filter(|&(_, &b)| b).map(|(i, _)| self.l + i as u64)
+    }
+
+    /** Return the factorizations of all integers in $[L, R]$ in increasing order, by dividing them with the base primes in bulk in $O((R - L) \log R + \sqrt{R})$ time. $L$ must be positive. */
+    pub fn factorize_all(&self) -> Vec<Factorization<u64>> {
+        assert!(self.l > 0, "0 cannot be factorized.");
+        let mut rem: Vec<u64> = (self.l..=self.r).collect();
+        let mut factors = vec![Vec::new(); rem.len()];
+        for &p in &self.base_primes {
+            let Some(mut x) = self.l.div_ceil(p).checked_mul(p) else {
+                continue;
+            };
+            while x <= self.r {
+                let i = (x - self.l) as usize;
+                let mut e = 0;
+                while rem[i].is_multiple_of(p) {
+                    rem[i] /= p;
+                    e += 1;
+                }
+                factors[i].push((p, e));
+                x = match x.checked_add(p) {
+                    Some(x) => x,
+                    None => break,
+                };
+            }
+        }
+        factors.into_iter().zip(rem).map(|(mut factors, rem)| {
+            if rem > 1 {
+                factors.push((rem, 1));
+            }
+            Factorization { factors }
+        }).collect()
+    }
+}
+
+/** `LucyHedgehog` computes $S(v) = \sum_{p \leq v} f(p)$ over the primes for all $v$ of the form $\lfloor n/i \rfloor$ by Lucy_Hedgehog's algorithm, where $f$ is completely multiplicative, in $O(n^{3/4} / \log n)$ time and $O(\sqrt{n})$ space. It starts from $\sum_{2 \leq k \leq v} f(k)$ and sieves out the composites by $S(v) \leftarrow S(v) - f(p)(S(\lfloor v/p \rfloor) - S(p - 1))$ for every prime $p \leq \sqrt{v}$. `T` can be any ring such as `u64`, `u128` or `ModInt`. */
+#[derive(Clone, Debug)]
+pub struct LucyHedgehog<T> {
+    n: u64,
+    r: u64,
+    small: Vec<T>,
+    large: Vec<T>,
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> LucyHedgehog<T> {
+    /** New a `LucyHedgehog` for a completely multiplicative $f$ given by `f` and its prefix sum $\sum_{2 \leq k \leq v} f(k)$ given by `prefix` (which must return $0$ for $v < 2$). */
+    pub fn new(n: u64, f: impl Fn(u64) -> T, prefix: impl Fn(u64) -> T) -> LucyHedgehog<T> {
+        let r = n.isqrt();
+        let mut small: Vec<T> = (0..=r).map(&prefix).collect();
+        let mut large: Vec<T> = (0..=r).map(|i| prefix(n.checked_div(i).unwrap_or(0))).collect();
+        let mut composite = vec![false; r as usize + 1];
+        for p in 2..=r {
+            if composite[p as usize] {
+                continue;
+            }
+            for j in (p * p..=r).step_by(p as usize) {
+                composite[j as usize] = true;
+            }
+            let sp = small[p as usize - 1];
+            let fp = f(p);
+            let p2 = p * p;
+            for i in 1..=r.min(n / p2) {
+                let d = i * p;
+                let sv = if d <= r { large[d as usize] } else { small[(n / d) as usize] };
+                large[i as usize] = large[i as usize] - fp * (sv - sp);
+            }
+            for v in (p2..=r).rev() {
+                small[v as usize] = small[v as usize] - fp * (small[(v / p) as usize] - sp);
+            }
+        }
+        LucyHedgehog { n, r, small, large }
+    }
+
+    /** Return $S(v)$ where `v` must be of the form $\lfloor n/i \rfloor$. */
+    pub fn get(&self, v: u64) -> T {
+        if v <= self.r {
+            self.small[v as usize]
+        } else {
+            assert!(v <= self.n && self.n / (self.n / v) == v, "`v` must be of the form n / i.");
+            self.large[(self.n / v) as usize]
+        }
+    }
+}
+
+/** `prime_count` returns the number of primes not larger than `n` by `LucyHedgehog` in $O(n^{3/4} / \log n)$ time. */
+pub fn prime_count(n: u64) -> u64 {
+    LucyHedgehog::new(n, |_| 1u64, |v| v.saturating_sub(1)).get(n)
+}
+
+/** `prime_sum` returns the sum of the primes not larger than `n` by `LucyHedgehog` in $O(n^{3/4} / \log n)$ time. */
+pub fn prime_sum(n: u64) -> u128 {
+    LucyHedgehog::new(n, |p| p as u128, |v| (v as u128 * (v as u128 + 1) / 2).saturating_sub(1)).get(n)
+}
+
+/** The ranges with fewer candidates than `PRIME_GEN_SMALL_RANGE` are enumerated by `PrimeGenerator` instead of rejection sampling, so that it stops on the ranges without any prime. */
+const PRIME_GEN_SMALL_RANGE: u128 = 1 << 16;
+
+/** The number of rejected draws after which `PrimeGenerator` falls back to enumerating the whole range. If a fraction $\rho$ of the candidates in the range is accepted, the fallback happens with probability $(1 - \rho)^{2^{20}} \approx e^{-2^{20} \rho}$, which is negligible for the density of primes (even safe primes) in wide ranges, but not for a range with only a few of them. */
+const PRIME_GEN_MAX_DRAWS: u32 = 1 << 20;
+
+/** Test the primality of `n` with the method of `primality_tester` matching the width of `T`. */
+fn is_prime_as<T: UnsignedInt, PT: PrimalityTest>(primality_tester: &mut PT, n: u128) -> bool {
+    match T::BITS {
+        0..=32 => primality_tester.is_prime_u32(&(n as u32)),
+        33..=64 => primality_tester.is_prime_u64(&(n as u64)),
+        _ => primality_tester.is_prime_u128(&n),
+    }
+}
+
+/** Return the bounds of `range` as an inclusive interval, or `None` if it is empty. */
+fn inclusive_bounds<T: UnsignedInt>(range: &impl RangeBounds<T>) -> Option<(u128, u128)> {
+    let lo = match range.start_bound() {
+        Bound::Included(x) => x.as_u128(),
+        Bound::Excluded(x) => x.as_u128().checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+    let hi = match range.end_bound() {
+        Bound::Included(x) => x.as_u128(),
+        Bound::Excluded(x) => x.as_u128().checked_sub(1)?,
+        Bound::Unbounded => T::MAX.as_u128(),
+    };
+    if lo <= hi { Some((lo, hi)) } else { None }
+}
+
+/** `PrimeGenerator` generates random primes (e.g. for hashing moduli and test data) by combining an `IntGenerator` with any `PrimalityTest`. Every generator is uniform over the primes of the requested form in the range: it draws uniform candidates and rejects the non-primes, which takes $O(\log n)$ primality tests in expectation for plain primes and $O(\log^2 n)$ for safe primes. Small ranges are enumerated instead, and so are the large ranges after $2^{20}$ rejected draws, which takes time linear in the width of the range. So the result is uniform in either case and `None` is returned when there is no such prime, but a wide range with few such primes may be slow. */
+#[derive(Clone, Debug)]
+pub struct PrimeGenerator<RNG: Pseudorandom64, PT: PrimalityTest> {
+    rng: IntGenerator<RNG>,
+    primality_tester: PT,
+}
+
+impl<RNG: Pseudorandom64, PT: PrimalityTest> PrimeGenerator<RNG, PT> {
+    /** New a `PrimeGenerator` with a given pseudorandom 64-bit number generator `rng` and a given primality tester `primality_tester`. */
+    pub fn new(rng: &RNG, primality_tester: &PT) -> PrimeGenerator<RNG, PT> {
+        PrimeGenerator {
+            rng: IntGenerator::new(rng),
+            primality_tester: primality_tester.clone(),
+        }
+    }
+
+    /** Return a uniform random index in $[lo, hi]$ accepted by `accept`, or `None` if there is none. The large ranges are sampled at most `PRIME_GEN_MAX_DRAWS` times before enumerating them, so the result stays uniform and the sampling never loops forever. */
+    fn gen_accepted(&mut self, lo: u128, hi: u128, mut accept: impl FnMut(&mut PT, u128) -> bool) -> Option<u128> {
+        if hi - lo >= PRIME_GEN_SMALL_RANGE {
+            for _ in 0..PRIME_GEN_MAX_DRAWS {
+                let i = self.rng.gen_range(lo..=hi);
+                if accept(&mut self.primality_tester, i) {
+                    return Some(i);
+                }
+            }
+        }
+        let candidates: Vec<u128> = (lo..=hi).filter(|&i| accept(&mut self.primality_tester, i)).collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        Some(candidates[self.rng.gen_range(0..candidates.len())])
+    }
+
+    /** Return a uniform random prime in `range`, or `None` if there is no prime in it. */
+    pub fn gen_prime_in_range<T: UnsignedInt>(&mut self, range: impl RangeBounds<T>) -> Option<T> {
+        let (lo, hi) = inclusive_bounds(&range)?;
+        self.gen_accepted(lo, hi, |pt, p| is_prime_as::<T, PT>(pt, p)).map(T::from_u128)
+    }
+
+    /** Return a uniform random safe prime $p$ (i.e. $(p - 1)/2$ is also a prime) in `range`, or `None` if there is no safe prime in it. All the safe primes larger than $7$ are $11 \bmod 12$, so the other candidates are rejected without primality tests. */
+    pub fn gen_safe_prime_in_range<T: UnsignedInt>(&mut self, range: impl RangeBounds<T>) -> Option<T> {
+        let (lo, hi) = inclusive_bounds(&range)?;
+        self.gen_accepted(lo, hi, |pt, p| {
+            (p == 5 || p == 7 || p % 12 == 11) && is_prime_as::<T, PT>(pt, p / 2) && is_prime_as::<T, PT>(pt, p)
+        }).map(T::from_u128)
+    }
+
+    /** Return a uniform random NTT-friendly prime $p = k \cdot 2^m + 1$ (so that $\mathbf{Z}_p$ has the $2^m$-th roots of unity) in `range`, or `None` if there is no such prime in it. It draws $k$ instead of $p$, so the expected number of primality tests is $O(\log n)$ as well. `m` must be smaller than the width of `T`. */
+    pub fn gen_ntt_prime_in_range<T: UnsignedInt>(&mut self, m: u32, range: impl RangeBounds<T>) -> Option<T> {
+        assert!(m < T::BITS, "`m` must be smaller than the width of the integers.");
+        let (lo, hi) = inclusive_bounds(&range)?;
+        let k_lo = if lo <= 2 { 1 } else { ((lo - 2) >> m) + 1 };
+        let k_hi = hi.checked_sub(1)? >> m;
+        if k_lo > k_hi {
+            return None;
+        }
+        self.gen_accepted(k_lo, k_hi, |pt, k| is_prime_as::<T, PT>(pt, (k << m) + 1)).map(|k| T::from_u128((k << m) + 1))
+    }
+
+    pub fn gen_prime_in_range_u32(&mut self, range: Range<u32>) -> Option<u32> {
+        self.gen_prime_in_range(range)
+    }
+
+    pub fn gen_prime_in_range_u64(&mut self, range: Range<u64>) -> Option<u64> {
+        self.gen_prime_in_range(range)
+    }
+
+    pub fn gen_prime_in_range_u128(&mut self, range: Range<u128>) -> Option<u128> {
+        self.gen_prime_in_range(range)
+    }
+
+    pub fn gen_safe_prime_in_range_u32(&mut self, range: Range<u32>) -> Option<u32> {
+        self.gen_safe_prime_in_range(range)
+    }
+
+    pub fn gen_safe_prime_in_range_u64(&mut self, range: Range<u64>) -> Option<u64> {
+        self.gen_safe_prime_in_range(range)
+    }
+
+    pub fn gen_safe_prime_in_range_u128(&mut self, range: Range<u128>) -> Option<u128> {
+        self.gen_safe_prime_in_range(range)
+    }
+
+    pub fn gen_ntt_prime_in_range_u32(&mut self, m: u32, range: Range<u32>) -> Option<u32> {
+        self.gen_ntt_prime_in_range(m, range)
+    }
+
+    pub fn gen_ntt_prime_in_range_u64(&mut self, m: u32, range: Range<u64>) -> Option<u64> {
+        self.gen_ntt_prime_in_range(m, range)
+    }
+
+    pub fn gen_ntt_prime_in_range_u128(&mut self, m: u32, range: Range<u128>) -> Option<u128> {
+        self.gen_ntt_prime_in_range(m, range)
+    }
+}
+
+/** `NextPrimes` iterates over the primes larger than a given integer in increasing order by testing the integers one by one with the given `PrimalityTest`, until the width of `T` overflows. Each step takes $O(\log n)$ primality tests on average. */
+#[derive(Clone, Debug)]
+pub struct NextPrimes<PT: PrimalityTest, T: UnsignedInt> {
+    primality_tester: PT,
+    cur: Option<T>,
+}
+
+impl<PT: PrimalityTest, T: UnsignedInt> NextPrimes<PT, T> {
+    /** New a `NextPrimes` over the primes larger than `n` with a given primality tester `primality_tester`. */
+    pub fn new(primality_tester: &PT, n: T) -> NextPrimes<PT, T> {
+        NextPrimes {
+            primality_tester: primality_tester.clone(),
+            cur: Some(n),
+        }
+    }
+}
+
+impl<PT: PrimalityTest, T: UnsignedInt> Iterator for NextPrimes<PT, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            self.cur = self.cur?.checked_add(T::ONE);
+            let x = self.cur?;
+            if is_prime_as::<T, PT>(&mut self.primality_tester, x.as_u128()) {
+                return Some(x);
+            }
+        }
+    }
+}
+
+/** `PrevPrimes` iterates over the primes smaller than a given integer in decreasing order by testing the integers one by one with the given `PrimalityTest`, until $2$. Each step takes $O(\log n)$ primality tests on average. */
+#[derive(Clone, Debug)]
+pub struct PrevPrimes<PT: PrimalityTest, T: UnsignedInt> {
+    primality_tester: PT,
+    cur: Option<T>,
+}
+
+impl<PT: PrimalityTest, T: UnsignedInt> PrevPrimes<PT, T> {
+    /** New a `PrevPrimes` over the primes smaller than `n` with a given primality tester `primality_tester`. */
+    pub fn new(primality_tester: &PT, n: T) -> PrevPrimes<PT, T> {
+        PrevPrimes {
+            primality_tester: primality_tester.clone(),
+            cur: Some(n),
+        }
+    }
+}
+
+impl<PT: PrimalityTest, T: UnsignedInt> Iterator for PrevPrimes<PT, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            self.cur = self.cur.filter(|&x| x.as_u128() > 2).map(|x| x - T::ONE);
+            let x = self.cur?;
+            if is_prime_as::<T, PT>(&mut self.primality_tester, x.as_u128()) {
+                return Some(x);
+            }
+        }
+    }
+}
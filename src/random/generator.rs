@@ -1,5 +1,5 @@
-use std::{collections::HashSet, ops::Range, time::SystemTime};
-use crate::math::basic::{from_to};
+use std::{collections::HashSet, ops::{Bound, Range, RangeBounds}, time::SystemTime};
+use crate::math::integer::Integer;
 
 
 /** `Pseudorandom64` introduces a trait for pseudorandom 64-bit unsigned integers. */
@@ -42,7 +42,7 @@ impl<RNG: Pseudorandom64> IntGenerator<RNG> {
         }
     }
 
-    pub fn gen_u8(&mut self) -> u8 {
+    fn gen_bits_u8(&mut self) -> u8 {
         if self.cnt_1 == 8 {
             self.v_1 = self.rng.gen();
             self.cnt_1 = 1;
@@ -53,11 +53,7 @@ impl<RNG: Pseudorandom64> IntGenerator<RNG> {
         (self.v_1 & 0xff) as u8
     }
 
-    pub fn gen_i8(&mut self) -> i8 {
-        from_to::u8i8(self.gen_u8())
-    }
-
-    pub fn gen_u16(&mut self) -> u16 {
+    fn gen_bits_u16(&mut self) -> u16 {
         if self.cnt_2 == 8 {
             self.v_2 = self.rng.gen();
             self.cnt_2 = 2;
@@ -68,11 +64,7 @@ impl<RNG: Pseudorandom64> IntGenerator<RNG> {
         (self.v_2 & 0xffff) as u16
     }
 
-    pub fn gen_i16(&mut self) -> i16 {
-        from_to::u16i16(self.gen_u16())
-    }
-
-    pub fn gen_u32(&mut self) -> u32 {
+    fn gen_bits_u32(&mut self) -> u32 {
         if self.cnt_4 == 8 {
             self.v_4 = self.rng.gen();
             self.cnt_4 = 4;
@@ -83,522 +75,226 @@ impl<RNG: Pseudorandom64> IntGenerator<RNG> {
         (self.v_4 & 0xffffffff) as u32
     }
 
+    /** Generate a `T`-type integer uniformly. */
+    pub fn gen<T: Integer>(&mut self) -> T {
+        let bits = match T::BITS {
+            8 => self.gen_bits_u8() as u128,
+            16 => self.gen_bits_u16() as u128,
+            32 => self.gen_bits_u32() as u128,
+            64 => self.rng.gen() as u128,
+            128 => (self.rng.gen() as u128) << 64 | (self.rng.gen() as u128),
+            _ => panic!("Unsupported type."),
+        };
+        T::from_ordered_unsigned(T::Unsigned::from_u128(bits))
+    }
+
+    /** Return the bounds of `range` as an inclusive interval of `T::Unsigned` by `Integer::to_ordered_unsigned`. Panic if `range` is empty. */
+    fn ordered_bounds<T: Integer>(range: &impl RangeBounds<T>) -> (T::Unsigned, T::Unsigned) {
+        let lo = match range.start_bound() {
+            Bound::Included(x) => Some(x.to_ordered_unsigned()),
+            Bound::Excluded(x) => x.to_ordered_unsigned().checked_add(T::Unsigned::ONE),
+            Bound::Unbounded => Some(T::Unsigned::ZERO),
+        };
+        let hi = match range.end_bound() {
+            Bound::Included(x) => Some(x.to_ordered_unsigned()),
+            Bound::Excluded(x) => x.to_ordered_unsigned().checked_sub(T::Unsigned::ONE),
+            Bound::Unbounded => Some(T::Unsigned::MAX),
+        };
+        match (lo, hi) {
+            (Some(lo), Some(hi)) if lo <= hi => (lo, hi),
+            _ => panic!("The range is invalid."),
+        }
+    }
+
+    /** Generate a `T::Unsigned` in $[lo, hi]$ uniformly by rejection sampling. */
+    fn gen_ordered<T: Integer>(&mut self, lo: T::Unsigned, hi: T::Unsigned) -> T::Unsigned {
+        let range_size_1 = hi - lo;
+        if range_size_1 == T::Unsigned::MAX {
+            return self.gen::<T::Unsigned>();
+        }
+        let range_size = range_size_1 + T::Unsigned::ONE;
+        if range_size == T::Unsigned::ONE {
+            return lo;
+        }
+        let bound = (T::Unsigned::MAX - range_size + T::Unsigned::ONE) / range_size * range_size + range_size_1;
+        loop {
+            let x = self.gen::<T::Unsigned>();
+            if x <= bound {
+                return x % range_size + lo;
+            }
+        }
+    }
+
+    /** Generate a `T`-type integer in `range` uniformly. `range` can be any of `a..b`, `a..=b`, `a..`, `..b` and `..`. */
+    pub fn gen_range<T: Integer>(&mut self, range: impl RangeBounds<T>) -> T {
+        let (lo, hi) = Self::ordered_bounds(&range);
+        T::from_ordered_unsigned(self.gen_ordered::<T>(lo, hi))
+    }
+
+    /** Generate `k` `T`-type integers in `range`. If `can_repeat=true` then generating repeated integers is allowed, otherwise all generated integers are distinct. $O(k)$ time with `can_repeat=true`. Expected $O(k)$ time with `can_repeat=false`. */
+    pub fn gen_range_k<T: Integer>(&mut self, range: impl RangeBounds<T>, k: usize, can_repeat: bool) -> Vec<T> {
+        let (lo, hi) = Self::ordered_bounds(&range);
+        
+        if can_repeat {
+            return (0..k).map(|_| T::from_ordered_unsigned(self.gen_ordered::<T>(lo, hi))).collect();
+        } 
+
+        let range_size_1 = (hi - lo).as_u128();
+
+        if k > 0 && range_size_1 < (k - 1) as u128 {
+            panic!("The range is too small to generate {} numbers.", k);
+        }
+
+        if range_size_1 >= (k as u128) * 5 {
+            let mut set = HashSet::new();
+            while set.len() < k {
+                set.insert(self.gen_ordered::<T>(lo, hi));
+            }
+            return set.into_iter().map(T::from_ordered_unsigned).collect();
+        }
+
+        let mut x = lo;
+        let mut ret = vec![T::from_ordered_unsigned(x)];
+        while x < hi {
+            x += T::Unsigned::ONE;
+            ret.push(T::from_ordered_unsigned(x));
+        }
+        self.random_shuffle(&mut ret);
+        ret.truncate(k);
+        ret
+    }
+
+    pub fn gen_u8(&mut self) -> u8 {
+        self.gen()
+    }
+
+    pub fn gen_i8(&mut self) -> i8 {
+        self.gen()
+    }
+
+    pub fn gen_u16(&mut self) -> u16 {
+        self.gen()
+    }
+
+    pub fn gen_i16(&mut self) -> i16 {
+        self.gen()
+    }
+
+    pub fn gen_u32(&mut self) -> u32 {
+        self.gen()
+    }
+
     pub fn gen_i32(&mut self) -> i32 {
-        from_to::u32i32(self.gen_u32())
+        self.gen()
     }
 
     pub fn gen_u64(&mut self) -> u64 {
-        self.rng.gen()
+        self.gen()
     }
 
     pub fn gen_i64(&mut self) -> i64 {
-        from_to::u64i64(self.gen_u64())
+        self.gen()
     }
 
     pub fn gen_u128(&mut self) -> u128 {
-        (self.rng.gen() as u128) << 64 | (self.rng.gen() as u128)
+        self.gen()
     }
 
     pub fn gen_i128(&mut self) -> i128 {
-        from_to::u128i128(self.gen_u128())
+        self.gen()
     }
 
     pub fn gen_usize(&mut self) -> usize {
-        match std::mem::size_of::<usize>() {
-            4 => self.gen_u32() as usize,
-            8 => self.gen_u64() as usize,
-            _ => panic!("Unsupported type."),
-        }
+        self.gen()
     }
 
-    // #[deprecated="Please use gen_u32, gen_i32 .etc."]
-    // /** Generate a `T`-type integer. */
-    // pub fn gen<T: TryFrom<u64> + std::ops::BitOr<Output = T> + std::ops::Shl<usize, Output = T> + Copy>(&mut self) -> T where <T as TryFrom<u64>>::Error: std::fmt::Debug {
-
-    //     match std::mem::size_of::<T>() {
-    //         1 => {
-    //             if self.cnt_1 == 8 {
-    //                 self.v_1 = self.rng.gen();
-    //                 self.cnt_1 = 0;
-    //             }
-    //             let x: T = (self.v_1 & 0xf).try_into().unwrap();
-    //             let y: T = ((self.v_1 >> 4) & 0xf).try_into().unwrap();
-    //             self.cnt_1 += 1;
-    //             self.v_1 >>= 8;
-    //             x << 4 | y
-    //         }, 
-    //         2 => {
-    //             if self.cnt_2 == 8 {
-    //                 self.v_2 = self.rng.gen();
-    //                 self.cnt_2 = 0;
-    //             }
-    //             let x: T = (self.v_2 & 0xff).try_into().unwrap();
-    //             let y: T = ((self.v_2 >> 8) & 0xff).try_into().unwrap();
-    //             self.cnt_2 += 2;
-    //             self.v_2 >>= 16;
-    //             x << 8 | y
-    //         },
-    //         4 => {
-    //             if self.cnt_4 == 8 {
-    //                 self.v_4 = self.rng.gen();
-    //                 self.cnt_4 = 0;
-    //             }
-    //             let x: T = (self.v_4 & 0xffff).try_into().unwrap();
-    //             let y: T = ((self.v_4 >> 16) & 0xffff).try_into().unwrap();
-    //             self.cnt_4 += 4;
-    //             self.v_4 >>= 32;
-    //             x << 16 | y
-    //         },
-    //         8 => {
-    //             let v = self.rng.gen();
-    //             let x: T = (v & 0xffffffff).try_into().unwrap();
-    //             let y: T = ((v >> 32) & 0xffffffff).try_into().unwrap();
-    //             x << 32 | y
-    //         },
-    //         16 => {
-    //             let x: T = self.rng.gen().try_into().unwrap();
-    //             let y: T = self.rng.gen().try_into().unwrap();
-    //             x << 64 | y
-    //         },
-    //         _ => panic!("Unsupported type."),
-    //     }
-    // }
-
     pub fn gen_range_u8(&mut self, range: Range<u8>) -> u8 {
-        if range.start >= range.end {
-            panic!("The range {:?} is invalid.", range);
-        }
-        let range_size = range.end - range.start;
-        if range_size == 1 {
-            return range.start;
-        }
-        let mut bound = (255 - range_size + 1) / range_size * range_size + (range_size - 1);
-        loop {
-            let x = self.gen_u8();
-            if x <= bound {
-                return x % range_size + range.start;
-            }
-        }
+        self.gen_range(range)
     }
 
     pub fn gen_range_i8(&mut self, range: Range<i8>) -> i8 {
-        if range.start >= range.end {
-            panic!("The range {:?} is invalid.", range);
-        }
-        from_to::u8i8(self.gen_range_u8(from_to::i8u8(range.start)..from_to::i8u8(range.end)))
+        self.gen_range(range)
     }
 
     pub fn gen_range_u16(&mut self, range: Range<u16>) -> u16 {
-        if range.start >= range.end {
-            panic!("The range {:?} is invalid.", range);
-        }
-        let range_size = range.end - range.start;
-        if range_size == 1 {
-            return range.start;
-        }
-        let mut bound = (65535 - range_size + 1) / range_size * range_size + (range_size - 1);
-        loop {
-            let x = self.gen_u16();
-            if x <= bound {
-                return x % range_size + range.start;
-            }
-        }
+        self.gen_range(range)
     }
 
     pub fn gen_range_i16(&mut self, range: Range<i16>) -> i16 {
-        if range.start >= range.end {
-            panic!("The range {:?} is invalid.", range);
-        }
-        from_to::u16i16(self.gen_range_u16(from_to::i16u16(range.start)..from_to::i16u16(range.end)))
+        self.gen_range(range)
     }
 
     pub fn gen_range_u32(&mut self, range: Range<u32>) -> u32 {
-        if range.start >= range.end {
-            panic!("The range {:?} is invalid.", range);
-        }
-        let range_size = range.end - range.start;
-        if range_size == 1 {
-            return range.start;
-        }
-        let mut bound = (4294967295 - range_size + 1) / range_size * range_size + (range_size - 1);
-        loop {
-            let x = self.gen_u32();
-            if x <= bound {
-                return x % range_size + range.start;
-            }
-        }
+        self.gen_range(range)
     }
 
     pub fn gen_range_i32(&mut self, range: Range<i32>) -> i32 {
-        if range.start >= range.end {
-            panic!("The range {:?} is invalid.", range);
-        }
-        from_to::u32i32(self.gen_range_u32(from_to::i32u32(range.start)..from_to::i32u32(range.end)))
+        self.gen_range(range)
     }
 
     pub fn gen_range_u64(&mut self, range: Range<u64>) -> u64 {
-        if range.start >= range.end {
-            panic!("The range {:?} is invalid.", range);
-        }
-        let range_size = range.end - range.start;
-        if range_size == 1 {
-            return range.start;
-        }
-        let mut bound = (18446744073709551615 - range_size + 1) / range_size * range_size + (range_size - 1);
-        loop {
-            let x = self.gen_u64();
-            if x <= bound {
-                return x % range_size + range.start;
-            }
-        }
+        self.gen_range(range)
     }
 
     pub fn gen_range_i64(&mut self, range: Range<i64>) -> i64 {
-        if range.start >= range.end {
-            panic!("The range {:?} is invalid.", range);
-        }
-        from_to::u64i64(self.gen_range_u64(from_to::i64u64(range.start)..from_to::i64u64(range.end)))
+        self.gen_range(range)
     }
 
     pub fn gen_range_u128(&mut self, range: Range<u128>) -> u128 {
-        if range.start >= range.end {
-            panic!("The range {:?} is invalid.", range);
-        }
-        let range_size = range.end - range.start;
-        if range_size == 1 {
-            return range.start;
-        }
-        let mut bound = (340282366920938463463374607431768211455 - range_size + 1) / range_size * range_size + (range_size - 1);
-        loop {
-            let x = self.gen_u128();
-            if x <= bound {
-                return x % range_size + range.start;
-            }
-        }
+        self.gen_range(range)
     }
 
     pub fn gen_range_i128(&mut self, range: Range<i128>) -> i128 {
-        if range.start >= range.end {
-            panic!("The range {:?} is invalid.", range);
-        }
-        from_to::u128i128(self.gen_range_u128(from_to::i128u128(range.start)..from_to::i128u128(range.end)))
+        self.gen_range(range)
     }
 
     pub fn gen_range_usize(&mut self, range: Range<usize>) -> usize {
-        if range.start >= range.end {
-            panic!("The range {:?} is invalid.", range);
-        }
-        self.gen_usize() % (range.end - range.start) + range.start
+        self.gen_range(range)
     }
-    
-    // #[deprecated="Please use gen_range_u32, gen_range_i32 .etc."]
-    // /** Generate an integer in `range` randomly. */
-    // pub fn gen_range<T: TryFrom<u64> + std::ops::BitOr<Output = T> + std::ops::Shl<usize, Output = T> + std::ops::Sub<Output = T> + std::ops::Rem<Output = T>  + std::ops::Add<Output = T> + Copy>(&mut self, range: Range<T>) -> T where <T as TryFrom<u64>>::Error: std::fmt::Debug {
-    //     self.gen::<T>() % (range.end - range.start) + range.start
-    // }
-
-    // #[deprecated="Please use gen_range_k_u32, gen_range_k_i32 .etc."]
-    // /** Random `k` integers from `range`. $O(k)$ time with `can_repeat=true`. Expected $O(k)$ time with `can_repeat=false`. */
-    // pub fn gen_range_k
-    //     <T:
-    //     TryFrom<u64> + std::ops::BitOr<Output = T> + std::ops::Shl<usize, Output = T> + std::ops::Sub<Output = T> + std::ops::Rem<Output = T>  + std::ops::Add<Output = T> + Copy +// for gen_range
-    //     TryInto<u128> + std::hash::Hash + Copy + std::cmp::PartialOrd + std::cmp::Eq + std::ops::Add<Output = T> + std::ops::Sub<Output = T> + std::ops::Mul<Output = T> + Sized
-    //     >
-    //     (&mut self, range: Range<T>, k: usize, can_repeat: bool) -> Vec<T> 
-    //     where 
-    //     <T as TryFrom<u64>>::Error: std::fmt::Debug, //for gen_range_k
-    //     std::ops::Range<T>: Iterator, std::ops::RangeInclusive<T>: Iterator<Item = T>, <T as TryInto<u128>>::Error: std::fmt::Debug 
-    //     { 
-    //     if range.start >= range.end {
-    //         panic!("The range is invalid.");
-    //     }
-        
-    //     if can_repeat {
-    //         let mut ret = Vec::new();
-    //         for _ in 0..k {
-    //             ret.push(self.gen_range(range.clone()));
-    //         }
-    //         return ret;
-    //     } 
-
-    //     let range_len: u128 = {
-    //         if range.start >= 0.try_into().unwrap() {
-    //             let start: u128 = range.start.try_into().unwrap();
-    //             let end: u128 = range.end.try_into().unwrap();
-    //             end - start
-    //         } else {
-    //             let zero: T = 0.try_into().unwrap();
-    //             let start: u128 = (zero - range.start).try_into().unwrap();
-    //             let end: u128 = range.end.try_into().unwrap();
-    //             end + start
-    //         }
-    //     };
-
-    //     if range_len < k as u128 {
-    //         panic!("The range is too small to generate {} numbers.", k);
-    //     }
-
-    //     if range_len > (k as u128) * 5 {
-    //         let mut HashSet = std::collections::HashSet::new();
-    //         while HashSet.len() < k {
-    //             HashSet.insert(self.gen_range(range.clone()));
-    //         }
-    //         return HashSet.into_iter().collect::<Vec<T>>();
-    //     }
-
-    //     let mut x = range.start.clone();
-    //     let mut ret = vec![];
-    //     let one: T = 1.try_into().unwrap();
-    //     while x < range.end {
-    //         ret.push(x.clone());
-    //         x = x + one;
-    //     }
-    //     self.random_shuffle(&mut ret);
-    //     ret[0..k].to_vec()
-    // }
 
     pub fn gen_range_k_u8(&mut self, range: Range<u8>, k: usize, can_repeat: bool) -> Vec<u8> {
-        if range.start >= range.end {
-            panic!("The range is invalid.");
-        }
-        
-        if can_repeat {
-            let mut ret = Vec::new();
-            for _ in 0..k {
-                ret.push(self.gen_range_u8(range.clone()));
-            }
-            return ret;
-        } 
-
-        let range_len = (range.end - range.start) as u128;
-
-        if range_len < k as u128 {
-            panic!("The range is too small to generate {} numbers.", k);
-        }
-
-        if range_len > (k as u128) * 5 {
-            let mut HashSet = std::collections::HashSet::new();
-            while HashSet.len() < k {
-                HashSet.insert(self.gen_range_u8(range.clone()));
-            }
-            return HashSet.into_iter().collect::<Vec<u8>>();
-        }
-
-        let mut x = range.start;
-        let mut ret = vec![];
-        while x < range.end {
-            ret.push(x);
-            x += 1;
-        }
-        self.random_shuffle(&mut ret);
-        ret[0..k].to_vec()
+        self.gen_range_k(range, k, can_repeat)
     }
 
     pub fn gen_range_k_i8(&mut self, range: Range<i8>, k: usize, can_repeat: bool) -> Vec<i8> {
-        self.gen_range_k_u8(from_to::i8u8(range.start)..from_to::i8u8(range.end), k, can_repeat).iter().map(|&x| from_to::u8i8(x)).collect::<Vec<i8>>()
+        self.gen_range_k(range, k, can_repeat)
     }
 
     pub fn gen_range_k_u16(&mut self, range: Range<u16>, k: usize, can_repeat: bool) -> Vec<u16> {
-        if range.start >= range.end {
-            panic!("The range is invalid.");
-        }
-        
-        if can_repeat {
-            let mut ret = Vec::new();
-            for _ in 0..k {
-                ret.push(self.gen_range_u16(range.clone()));
-            }
-            return ret;
-        } 
-
-        let range_len = (range.end - range.start) as u128;
-
-        if range_len < k as u128 {
-            panic!("The range is too small to generate {} numbers.", k);
-        }
-
-        if range_len > (k as u128) * 5 {
-            let mut HashSet = std::collections::HashSet::new();
-            while HashSet.len() < k {
-                HashSet.insert(self.gen_range_u16(range.clone()));
-            }
-            return HashSet.into_iter().collect::<Vec<u16>>();
-        }
-
-        let mut x = range.start;
-        let mut ret = vec![];
-        while x < range.end {
-            ret.push(x);
-            x += 1;
-        }
-        self.random_shuffle(&mut ret);
-        ret[0..k].to_vec()
+        self.gen_range_k(range, k, can_repeat)
     }
 
     pub fn gen_range_k_i16(&mut self, range: Range<i16>, k: usize, can_repeat: bool) -> Vec<i16> {
-        self.gen_range_k_u16(from_to::i16u16(range.start)..from_to::i16u16(range.end), k, can_repeat).iter().map(|&x| from_to::u16i16(x)).collect::<Vec<i16>>()
+        self.gen_range_k(range, k, can_repeat)
     }
 
     pub fn gen_range_k_u32(&mut self, range: Range<u32>, k: usize, can_repeat: bool) -> Vec<u32> {
-        if range.start >= range.end {
-            panic!("The range is invalid.");
-        }
-        
-        if can_repeat {
-            let mut ret = Vec::new();
-            for _ in 0..k {
-                ret.push(self.gen_range_u32(range.clone()));
-            }
-            return ret;
-        } 
-
-        let range_len = (range.end - range.start) as u128;
-
-        if range_len < k as u128 {
-            panic!("The range is too small to generate {} numbers.", k);
-        }
-
-        if range_len > (k as u128) * 5 {
-            let mut HashSet = std::collections::HashSet::new();
-            while HashSet.len() < k {
-                HashSet.insert(self.gen_range_u32(range.clone()));
-            }
-            return HashSet.into_iter().collect::<Vec<u32>>();
-        }
-
-        let mut x = range.start;
-        let mut ret = vec![];
-        while x < range.end {
-            ret.push(x);
-            x += 1;
-        }
-        self.random_shuffle(&mut ret);
-        ret[0..k].to_vec()
+        self.gen_range_k(range, k, can_repeat)
     }
 
     pub fn gen_range_k_i32(&mut self, range: Range<i32>, k: usize, can_repeat: bool) -> Vec<i32> {
-        self.gen_range_k_u32(from_to::i32u32(range.start)..from_to::i32u32(range.end), k, can_repeat).iter().map(|&x| from_to::u32i32(x)).collect::<Vec<i32>>()
+        self.gen_range_k(range, k, can_repeat)
     }
 
     pub fn gen_range_k_u64(&mut self, range: Range<u64>, k: usize, can_repeat: bool) -> Vec<u64> {
-        if range.start >= range.end {
-            panic!("The range is invalid.");
-        }
-        
-        if can_repeat {
-            let mut ret = Vec::new();
-            for _ in 0..k {
-                ret.push(self.gen_range_u64(range.clone()));
-            }
-            return ret;
-        } 
-
-        let range_len = (range.end - range.start) as u128;
-
-        if range_len < k as u128 {
-            panic!("The range is too small to generate {} numbers.", k);
-        }
-
-        if range_len > (k as u128) * 5 {
-            let mut HashSet = std::collections::HashSet::new();
-            while HashSet.len() < k {
-                HashSet.insert(self.gen_range_u64(range.clone()));
-            }
-            return HashSet.into_iter().collect::<Vec<u64>>();
-        }
-
-        let mut x = range.start;
-        let mut ret = vec![];
-        while x < range.end {
-            ret.push(x);
-            x += 1;
-        }
-        self.random_shuffle(&mut ret);
-        ret[0..k].to_vec()
+        self.gen_range_k(range, k, can_repeat)
     }
 
     pub fn gen_range_k_i64(&mut self, range: Range<i64>, k: usize, can_repeat: bool) -> Vec<i64> {
-        self.gen_range_k_u64(from_to::i64u64(range.start)..from_to::i64u64(range.end), k, can_repeat).iter().map(|&x| from_to::u64i64(x)).collect::<Vec<i64>>()
+        self.gen_range_k(range, k, can_repeat)
     }
 
     pub fn gen_range_k_u128(&mut self, range: Range<u128>, k: usize, can_repeat: bool) -> Vec<u128> {
-        if range.start >= range.end {
-            panic!("The range is invalid.");
-        }
-        
-        if can_repeat {
-            let mut ret = Vec::new();
-            for _ in 0..k {
-                ret.push(self.gen_range_u128(range.clone()));
-            }
-            return ret;
-        } 
-
-        let range_len = range.end - range.start;
-
-        if range_len < k as u128 {
-            panic!("The range is too small to generate {} numbers.", k);
-        }
-
-        if range_len > (k as u128) * 5 {
-            let mut HashSet = std::collections::HashSet::new();
-            while HashSet.len() < k {
-                HashSet.insert(self.gen_range_u128(range.clone()));
-            }
-            return HashSet.into_iter().collect::<Vec<u128>>();
-        }
-
-        let mut x = range.start;
-        let mut ret = vec![];
-        while x < range.end {
-            ret.push(x);
-            x += 1;
-        }
-        self.random_shuffle(&mut ret);
-        ret[0..k].to_vec()
+        self.gen_range_k(range, k, can_repeat)
     }
 
     pub fn gen_range_k_i128(&mut self, range: Range<i128>, k: usize, can_repeat: bool) -> Vec<i128> {
-        self.gen_range_k_u128(from_to::i128u128(range.start)..from_to::i128u128(range.end), k, can_repeat).iter().map(|&x| from_to::u128i128(x)).collect::<Vec<i128>>()
+        self.gen_range_k(range, k, can_repeat)
     }
 
     pub fn gen_range_k_usize(&mut self, range: Range<usize>, k: usize, can_repeat: bool) -> Vec<usize> {
-        if range.start >= range.end {
-            panic!("The range is invalid.");
-        }
-        
-        if can_repeat {
-            let mut ret = Vec::new();
-            for _ in 0..k {
-                ret.push(self.gen_range_usize(range.clone()));
-            }
-            return ret;
-        } 
-
-        let range_len = range.end - range.start;
-
-        if range_len < k {
-            panic!("The range is too small to generate {} numbers.", k);
-        }
-
-        if range_len as u128 > (k as u128) * 5 {
-            let mut HashSet = std::collections::HashSet::new();
-            while HashSet.len() < k {
-                HashSet.insert(self.gen_range_usize(range.clone()));
-            }
-            return HashSet.into_iter().collect::<Vec<usize>>();
-        }
-
-        let mut x = range.start;
-        let mut ret = vec![];
-        while x < range.end {
-            ret.push(x);
-            x += 1;
-        }
-        self.random_shuffle(&mut ret);
-        ret[0..k].to_vec()
+        self.gen_range_k(range, k, can_repeat)
     }
-    
 
     /** Generate an element from vector `d` randomly. */
     pub fn gen_from_vec<T: Clone>(&mut self, d: &Vec<T>) -> T {